@@ -29,6 +29,7 @@ declare module 'vue' {
     QItemLabel: typeof import('quasar')['QItemLabel']
     QItemSection: typeof import('quasar')['QItemSection']
     QLayout: typeof import('quasar')['QLayout']
+    QLinearProgress: typeof import('quasar')['QLinearProgress']
     QList: typeof import('quasar')['QList']
     QPage: typeof import('quasar')['QPage']
     QPageContainer: typeof import('quasar')['QPageContainer']
@@ -1,40 +1,85 @@
 use sea_orm::DatabaseConnection;
-use tauri::State;
+use tauri::{AppHandle, Emitter, Runtime, State};
+use tauri_plugin_notification::NotificationExt;
 
-use crate::database::{category_service, record_service};
-use crate::entities::{category, expense_record};
+use crate::database::{
+    account_service, budget_service, category_service, exchange_rate_service, i18n, record_service,
+    recurring_service, settings_service, tag_service, DbHandle,
+};
+use crate::entities::{
+    account, budget, budget_allocation, category, exchange_rate, expense_record, recurring_rule,
+    tag, CategoryType, RecordType,
+};
+use crate::error::AppError;
 
-pub type DbState<'a> = State<'a, DatabaseConnection>;
+pub type DbState<'a> = State<'a, DbHandle>;
 
 // Category Commands
 #[tauri::command]
 pub async fn get_categories(
     db: DbState<'_>,
-    category_type: Option<String>,
-) -> Result<Vec<category::Model>, String> {
-    category_service::get_categories(&*db, category_type)
-        .await
-        .map_err(|e| e.to_string())
+    category_type: Option<CategoryType>,
+    include_archived: Option<bool>,
+    sort: Option<category_service::CategorySort>,
+    locale: Option<String>,
+) -> Result<Vec<category::Model>, AppError> {
+    let db = db.connection().await?;
+
+    let mut categories = category_service::get_categories(
+        &db,
+        category_type,
+        include_archived.unwrap_or(false),
+        sort.unwrap_or_default(),
+    )
+    .await?;
+
+    if let Some(locale) = locale.as_deref().and_then(i18n::Locale::parse) {
+        for category in categories.iter_mut() {
+            i18n::localize_category(category, locale);
+        }
+    }
+
+    Ok(categories)
+}
+
+#[tauri::command]
+pub async fn get_category_tree(
+    db: DbState<'_>,
+    category_type: Option<CategoryType>,
+    include_archived: Option<bool>,
+    locale: Option<String>,
+) -> Result<Vec<category_service::CategoryNode>, AppError> {
+    let db = db.connection().await?;
+
+    let mut tree =
+        category_service::get_category_tree(&db, category_type, include_archived.unwrap_or(false))
+            .await?;
+
+    if let Some(locale) = locale.as_deref().and_then(i18n::Locale::parse) {
+        i18n::localize_category_tree(&mut tree, locale);
+    }
+
+    Ok(tree)
 }
 
 #[tauri::command]
 pub async fn get_category_by_id(
     db: DbState<'_>,
     category_id: String,
-) -> Result<Option<category::Model>, String> {
-    category_service::get_category_by_id(&*db, &category_id)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<Option<category::Model>, AppError> {
+    let db = db.connection().await?;
+
+    category_service::get_category_by_id(&db, &category_id).await
 }
 
 #[tauri::command]
 pub async fn create_category(
     db: DbState<'_>,
     category_data: category_service::CategoryData,
-) -> Result<category::Model, String> {
-    category_service::create_category(&*db, category_data)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<category::Model, AppError> {
+    let db = db.connection().await?;
+
+    category_service::create_category(&db, category_data).await
 }
 
 #[tauri::command]
@@ -42,17 +87,323 @@ pub async fn update_category(
     db: DbState<'_>,
     category_id: String,
     category_data: category_service::CategoryData,
-) -> Result<category::Model, String> {
-    category_service::update_category(&*db, &category_id, category_data)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<category::Model, AppError> {
+    let db = db.connection().await?;
+
+    category_service::update_category(&db, &category_id, category_data).await
 }
 
 #[tauri::command]
-pub async fn delete_category(db: DbState<'_>, category_id: String) -> Result<(), String> {
-    category_service::delete_category(&*db, &category_id)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn move_category(
+    db: DbState<'_>,
+    category_id: String,
+    parent_id: Option<String>,
+) -> Result<category::Model, AppError> {
+    let db = db.connection().await?;
+
+    category_service::move_category(&db, &category_id, parent_id).await
+}
+
+#[tauri::command]
+pub async fn reorder_categories(
+    db: DbState<'_>,
+    category_ids: Vec<String>,
+) -> Result<(), AppError> {
+    let db = db.connection().await?;
+
+    category_service::reorder_categories(&db, category_ids).await
+}
+
+#[tauri::command]
+pub async fn delete_category(
+    db: DbState<'_>,
+    category_id: String,
+    mode: Option<category_service::CategoryDeleteMode>,
+) -> Result<(), AppError> {
+    let db = db.connection().await?;
+
+    category_service::delete_category(&db, &category_id, mode.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn set_category_archived(
+    db: DbState<'_>,
+    category_id: String,
+    archived: bool,
+) -> Result<category::Model, AppError> {
+    let db = db.connection().await?;
+
+    category_service::set_category_archived(&db, &category_id, archived).await
+}
+
+#[tauri::command]
+pub async fn merge_categories(
+    db: DbState<'_>,
+    source_category_id: String,
+    target_category_id: String,
+    archive_source: Option<bool>,
+) -> Result<u64, AppError> {
+    let db = db.connection().await?;
+
+    category_service::merge_categories(
+        &db,
+        &source_category_id,
+        &target_category_id,
+        archive_source.unwrap_or(false),
+    )
+    .await
+}
+
+// Account Commands
+#[tauri::command]
+pub async fn get_accounts(db: DbState<'_>) -> Result<Vec<account::Model>, AppError> {
+    let db = db.connection().await?;
+
+    account_service::get_accounts(&db).await
+}
+
+#[tauri::command]
+pub async fn get_account_by_id(
+    db: DbState<'_>,
+    id: i32,
+) -> Result<Option<account::Model>, AppError> {
+    let db = db.connection().await?;
+
+    account_service::get_account_by_id(&db, id).await
+}
+
+#[tauri::command]
+pub async fn create_account(
+    db: DbState<'_>,
+    account_data: account_service::AccountData,
+) -> Result<account::Model, AppError> {
+    let db = db.connection().await?;
+
+    account_service::create_account(&db, account_data).await
+}
+
+#[tauri::command]
+pub async fn update_account(
+    db: DbState<'_>,
+    id: i32,
+    account_data: account_service::AccountData,
+) -> Result<account::Model, AppError> {
+    let db = db.connection().await?;
+
+    account_service::update_account(&db, id, account_data).await
+}
+
+#[tauri::command]
+pub async fn delete_account(db: DbState<'_>, id: i32) -> Result<(), AppError> {
+    let db = db.connection().await?;
+
+    account_service::delete_account(&db, id).await
+}
+
+#[tauri::command]
+pub async fn get_account_balances(
+    db: DbState<'_>,
+) -> Result<Vec<account_service::AccountBalance>, AppError> {
+    let db = db.connection().await?;
+
+    account_service::get_account_balances(&db).await
+}
+
+// Currency Commands
+#[tauri::command]
+pub async fn get_base_currency(db: DbState<'_>) -> Result<String, AppError> {
+    let db = db.connection().await?;
+
+    settings_service::get_base_currency(&db).await
+}
+
+#[tauri::command]
+pub async fn set_base_currency(db: DbState<'_>, currency: String) -> Result<(), AppError> {
+    let db = db.connection().await?;
+
+    settings_service::set_base_currency(&db, &currency).await
+}
+
+#[tauri::command]
+pub async fn get_exchange_rates(
+    db: DbState<'_>,
+    currency: Option<String>,
+) -> Result<Vec<exchange_rate::Model>, AppError> {
+    let db = db.connection().await?;
+
+    exchange_rate_service::get_exchange_rates(&db, currency).await
+}
+
+#[tauri::command]
+pub async fn set_exchange_rate(
+    db: DbState<'_>,
+    rate_data: exchange_rate_service::ExchangeRateData,
+) -> Result<exchange_rate::Model, AppError> {
+    let db = db.connection().await?;
+
+    exchange_rate_service::set_exchange_rate(&db, rate_data).await
+}
+
+#[tauri::command]
+pub async fn delete_exchange_rate(db: DbState<'_>, id: i32) -> Result<(), AppError> {
+    let db = db.connection().await?;
+
+    exchange_rate_service::delete_exchange_rate(&db, id).await
+}
+
+// content 为 CSV 文件内容，由前端读取后传入
+#[tauri::command]
+pub async fn import_exchange_rates(db: DbState<'_>, content: String) -> Result<u64, AppError> {
+    let db = db.connection().await?;
+
+    exchange_rate_service::import_exchange_rates(&db, &content).await
+}
+
+// Recurring Rule Commands
+#[tauri::command]
+pub async fn get_recurring_rules(db: DbState<'_>) -> Result<Vec<recurring_rule::Model>, AppError> {
+    let db = db.connection().await?;
+
+    recurring_service::get_recurring_rules(&db).await
+}
+
+#[tauri::command]
+pub async fn create_recurring_rule(
+    db: DbState<'_>,
+    rule_data: recurring_service::RecurringRuleData,
+) -> Result<recurring_rule::Model, AppError> {
+    let db = db.connection().await?;
+
+    recurring_service::create_recurring_rule(&db, rule_data).await
+}
+
+#[tauri::command]
+pub async fn update_recurring_rule(
+    db: DbState<'_>,
+    id: i32,
+    rule_data: recurring_service::RecurringRuleData,
+) -> Result<recurring_rule::Model, AppError> {
+    let db = db.connection().await?;
+
+    recurring_service::update_recurring_rule(&db, id, rule_data).await
+}
+
+#[tauri::command]
+pub async fn delete_recurring_rule(db: DbState<'_>, id: i32) -> Result<(), AppError> {
+    let db = db.connection().await?;
+
+    recurring_service::delete_recurring_rule(&db, id).await
+}
+
+// Budget Commands
+#[tauri::command]
+pub async fn get_budgets(db: DbState<'_>) -> Result<Vec<budget::Model>, AppError> {
+    let db = db.connection().await?;
+
+    budget_service::get_budgets(&db).await
+}
+
+#[tauri::command]
+pub async fn create_budget(
+    db: DbState<'_>,
+    budget_data: budget_service::BudgetData,
+) -> Result<budget::Model, AppError> {
+    let db = db.connection().await?;
+
+    budget_service::create_budget(&db, budget_data, chrono::Utc::now()).await
+}
+
+#[tauri::command]
+pub async fn update_budget(
+    db: DbState<'_>,
+    id: i32,
+    budget_data: budget_service::BudgetData,
+) -> Result<budget::Model, AppError> {
+    let db = db.connection().await?;
+
+    budget_service::update_budget(&db, id, budget_data, chrono::Local::now().date_naive()).await
+}
+
+#[tauri::command]
+pub async fn delete_budget(db: DbState<'_>, id: i32) -> Result<(), AppError> {
+    let db = db.connection().await?;
+
+    budget_service::delete_budget(&db, id).await
+}
+
+// date 为 YYYY-MM-DD 本地日期，缺省为今天
+#[tauri::command]
+pub async fn get_budget_status(
+    db: DbState<'_>,
+    date: Option<String>,
+    week_start: Option<u32>,
+) -> Result<Vec<budget_service::BudgetStatus>, AppError> {
+    let db = db.connection().await?;
+    let date = match date {
+        Some(date) => parse_local_date(&date, "date")?,
+        None => chrono::Local::now().date_naive(),
+    };
+
+    // 默认周一为一周的第一天
+    budget_service::get_budget_status(&db, date, week_start.unwrap_or(1)).await
+}
+
+// month 为所在月份中任意一天的 YYYY-MM-DD，缺省为本月
+#[tauri::command]
+pub async fn set_budget_allocations(
+    db: DbState<'_>,
+    month: Option<String>,
+    allocations: Vec<budget_service::EnvelopeAllocation>,
+) -> Result<Vec<budget_allocation::Model>, AppError> {
+    let db = db.connection().await?;
+    let today = chrono::Local::now().date_naive();
+    let month = match month {
+        Some(month) => parse_local_date(&month, "month")?,
+        None => today,
+    };
+
+    budget_service::set_budget_allocations(&db, month, allocations, today).await
+}
+
+#[tauri::command]
+pub async fn get_envelope_summary(
+    db: DbState<'_>,
+    month: Option<String>,
+) -> Result<budget_service::EnvelopeSummary, AppError> {
+    let db = db.connection().await?;
+    let today = chrono::Local::now().date_naive();
+    let month = match month {
+        Some(month) => parse_local_date(&month, "month")?,
+        None => today,
+    };
+
+    budget_service::get_envelope_summary(&db, month, today).await
+}
+
+// Tag Commands
+#[tauri::command]
+pub async fn get_tags(db: DbState<'_>) -> Result<Vec<tag::Model>, AppError> {
+    let db = db.connection().await?;
+
+    tag_service::get_tags(&db).await
+}
+
+#[tauri::command]
+pub async fn update_tag(
+    db: DbState<'_>,
+    id: i32,
+    tag_data: tag_service::TagData,
+) -> Result<tag::Model, AppError> {
+    let db = db.connection().await?;
+
+    tag_service::update_tag(&db, id, tag_data).await
+}
+
+#[tauri::command]
+pub async fn delete_tag(db: DbState<'_>, id: i32) -> Result<(), AppError> {
+    let db = db.connection().await?;
+
+    tag_service::delete_tag(&db, id).await
 }
 
 // Record Commands
@@ -60,80 +411,279 @@ pub async fn delete_category(db: DbState<'_>, category_id: String) -> Result<(),
 pub async fn get_records(
     db: DbState<'_>,
     filter: Option<record_service::RecordFilter>,
-) -> Result<Vec<expense_record::Model>, String> {
-    record_service::get_records(&*db, filter)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<record_service::RecordPage, AppError> {
+    let db = db.connection().await?;
+
+    record_service::get_records(&db, filter).await
+}
+
+#[tauri::command]
+pub async fn search_records(
+    db: DbState<'_>,
+    query: String,
+    filter: Option<record_service::RecordFilter>,
+) -> Result<record_service::RecordPage, AppError> {
+    let db = db.connection().await?;
+
+    record_service::search_records(&db, &query, filter).await
 }
 
 #[tauri::command]
 pub async fn get_record_by_id(
     db: DbState<'_>,
     id: i32,
-) -> Result<Option<expense_record::Model>, String> {
-    record_service::get_record_by_id(&*db, id)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<Option<record_service::TaggedRecord>, AppError> {
+    let db = db.connection().await?;
+
+    let record = record_service::get_record_by_id(&db, id).await?;
+    match record {
+        Some(record) => Ok(record_service::with_tags(&db, vec![record]).await?.pop()),
+        None => Ok(None),
+    }
 }
 
 #[tauri::command]
-pub async fn create_record(
+pub async fn create_record<R: Runtime>(
+    app: AppHandle<R>,
     db: DbState<'_>,
     record_data: record_service::ExpenseRecordData,
-) -> Result<expense_record::Model, String> {
-    record_service::create_record(&*db, record_data)
-        .await
-        .map_err(|e| e.to_string())
+    locale: Option<String>,
+    week_start: Option<u32>,
+) -> Result<record_service::TaggedRecord, AppError> {
+    let db = db.connection().await?;
+
+    let record = record_service::create_record(&db, record_data).await?;
+    if record.record.record_type == RecordType::Expense {
+        notify_budget_alerts(
+            &app,
+            &db,
+            &record.record,
+            locale.as_deref(),
+            // 默认周一为一周的第一天
+            week_start.unwrap_or(1),
+        )
+        .await;
+    }
+
+    Ok(record)
 }
 
 #[tauri::command]
-pub async fn update_record(
+pub async fn update_record<R: Runtime>(
+    app: AppHandle<R>,
     db: DbState<'_>,
     id: i32,
     record_data: record_service::ExpenseRecordData,
-) -> Result<expense_record::Model, String> {
-    record_service::update_record(&*db, id, record_data)
-        .await
-        .map_err(|e| e.to_string())
+    locale: Option<String>,
+    week_start: Option<u32>,
+) -> Result<record_service::TaggedRecord, AppError> {
+    let db = db.connection().await?;
+
+    let record = record_service::update_record(&db, id, record_data).await?;
+    if record.record.record_type == RecordType::Expense {
+        notify_budget_alerts(
+            &app,
+            &db,
+            &record.record,
+            locale.as_deref(),
+            // 默认周一为一周的第一天
+            week_start.unwrap_or(1),
+        )
+        .await;
+    }
+
+    Ok(record)
+}
+
+// 记账后检查记录发生日期所在周期的预算是否越过提醒阈值，补记以前的支出也会提醒
+// 新触发的提醒发出 budget-alert 事件和系统通知，提醒失败只记录日志，不影响记账结果
+// 通知文本按界面传入的 locale 输出，缺省或无法识别时为简体中文；周预算按界面传入的 week_start 划分周期
+async fn notify_budget_alerts<R: Runtime>(
+    app: &AppHandle<R>,
+    db: &DatabaseConnection,
+    record: &expense_record::Model,
+    locale: Option<&str>,
+    week_start: u32,
+) {
+    let date = record
+        .occurred_at
+        .with_timezone(&chrono::Local)
+        .date_naive();
+    let locale = locale
+        .and_then(i18n::Locale::parse)
+        .unwrap_or(i18n::Locale::ZhCn);
+    let notices = match budget_service::check_budget_alerts(db, date, week_start, locale).await {
+        Ok(notices) => notices,
+        Err(e) => {
+            log::error!("Failed to check budget alerts: {}", e);
+            return;
+        }
+    };
+
+    for notice in notices {
+        if let Err(e) = app.emit("budget-alert", &notice) {
+            log::error!("Failed to emit budget-alert event: {}", e);
+        }
+
+        let (title, body) = i18n::budget_alert_message(&notice, locale);
+        if let Err(e) = app.notification().builder().title(title).body(body).show() {
+            log::error!("Failed to show budget notification: {}", e);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn create_transfer(
+    db: DbState<'_>,
+    transfer_data: record_service::TransferData,
+) -> Result<expense_record::Model, AppError> {
+    let db = db.connection().await?;
+
+    record_service::create_transfer(&db, transfer_data).await
+}
+
+#[tauri::command]
+pub async fn update_transfer(
+    db: DbState<'_>,
+    id: i32,
+    transfer_data: record_service::TransferData,
+) -> Result<expense_record::Model, AppError> {
+    let db = db.connection().await?;
+
+    record_service::update_transfer(&db, id, transfer_data).await
+}
+
+#[tauri::command]
+pub async fn delete_record(db: DbState<'_>, id: i32) -> Result<(), AppError> {
+    let db = db.connection().await?;
+
+    record_service::delete_record(&db, id).await
+}
+
+#[tauri::command]
+pub async fn get_trash(db: DbState<'_>) -> Result<Vec<expense_record::Model>, AppError> {
+    let db = db.connection().await?;
+
+    record_service::get_trash(&db).await
 }
 
 #[tauri::command]
-pub async fn delete_record(db: DbState<'_>, id: i32) -> Result<(), String> {
-    record_service::delete_record(&*db, id)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn restore_record(db: DbState<'_>, id: i32) -> Result<expense_record::Model, AppError> {
+    let db = db.connection().await?;
+
+    record_service::restore_record(&db, id).await
+}
+
+#[tauri::command]
+pub async fn purge_record(db: DbState<'_>, id: i32) -> Result<(), AppError> {
+    let db = db.connection().await?;
+
+    record_service::purge_record(&db, id).await
+}
+
+#[tauri::command]
+pub async fn empty_trash(db: DbState<'_>) -> Result<u64, AppError> {
+    let db = db.connection().await?;
+
+    record_service::empty_trash(&db).await
+}
+
+// 解析前端传入的 RFC 3339 时间字符串
+fn parse_date(
+    date_str: Option<String>,
+    field: &str,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, AppError> {
+    date_str
+        .map(|date_str| {
+            chrono::DateTime::parse_from_rfc3339(&date_str)
+                .map(|date| date.with_timezone(&chrono::Utc))
+                .map_err(|e| AppError::validation(field, format!("Invalid date: {}", e)))
+        })
+        .transpose()
 }
 
 #[tauri::command]
 pub async fn get_statistics(
     db: DbState<'_>,
-    record_type: Option<String>,
+    record_type: Option<RecordType>,
     start_date: Option<String>,
     end_date: Option<String>,
-) -> Result<String, String> {
-    let start_date = if let Some(date_str) = start_date {
-        Some(
-            chrono::DateTime::parse_from_rfc3339(&date_str)
-                .map_err(|e| format!("Invalid start date: {}", e))?
-                .with_timezone(&chrono::Utc),
-        )
-    } else {
-        None
-    };
+) -> Result<record_service::StatisticsReport, AppError> {
+    let db = db.connection().await?;
+    let start_date = parse_date(start_date, "start_date")?;
+    let end_date = parse_date(end_date, "end_date")?;
 
-    let end_date = if let Some(date_str) = end_date {
-        Some(
-            chrono::DateTime::parse_from_rfc3339(&date_str)
-                .map_err(|e| format!("Invalid end date: {}", e))?
-                .with_timezone(&chrono::Utc),
-        )
-    } else {
-        None
-    };
+    record_service::get_statistics(&db, record_type, start_date, end_date).await
+}
+
+#[tauri::command]
+pub async fn get_category_breakdown(
+    db: DbState<'_>,
+    record_type: RecordType,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    roll_up: Option<bool>,
+    locale: Option<String>,
+) -> Result<record_service::CategoryBreakdownReport, AppError> {
+    let db = db.connection().await?;
+    let start_date = parse_date(start_date, "start_date")?;
+    let end_date = parse_date(end_date, "end_date")?;
 
-    let result = record_service::get_statistics(&*db, record_type, start_date, end_date)
-        .await
-        .map_err(|e| e.to_string())?;
+    let mut report = record_service::get_category_breakdown(
+        &db,
+        record_type,
+        start_date,
+        end_date,
+        roll_up.unwrap_or(false),
+    )
+    .await?;
+
+    if let Some(locale) = locale.as_deref().and_then(i18n::Locale::parse) {
+        i18n::localize_category_breakdown(&mut report.categories, locale);
+    }
+
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn get_tag_breakdown(
+    db: DbState<'_>,
+    record_type: RecordType,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<record_service::TagBreakdownReport, AppError> {
+    let db = db.connection().await?;
+    let start_date = parse_date(start_date, "start_date")?;
+    let end_date = parse_date(end_date, "end_date")?;
+
+    record_service::get_tag_breakdown(&db, record_type, start_date, end_date).await
+}
+
+// 解析前端传入的 YYYY-MM-DD 本地日期
+fn parse_local_date(date_str: &str, field: &str) -> Result<chrono::NaiveDate, AppError> {
+    chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+        .map_err(|e| AppError::validation(field, format!("Invalid date: {}", e)))
+}
+
+#[tauri::command]
+pub async fn get_time_series(
+    db: DbState<'_>,
+    granularity: record_service::TimeGranularity,
+    start_date: String,
+    end_date: String,
+    week_start: Option<u32>,
+) -> Result<record_service::TimeSeries, AppError> {
+    let db = db.connection().await?;
+    let start_date = parse_local_date(&start_date, "start_date")?;
+    let end_date = parse_local_date(&end_date, "end_date")?;
 
-    Ok(result.to_string())
+    // 默认周一为一周的第一天
+    record_service::get_time_series(
+        &db,
+        granularity,
+        start_date,
+        end_date,
+        week_start.unwrap_or(1),
+    )
+    .await
 }
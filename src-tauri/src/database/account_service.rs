@@ -0,0 +1,347 @@
+use rust_decimal::Decimal;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::database::record_service;
+use crate::database::validation::{self, Validator};
+use crate::entities::{
+    account, expense_record, recurring_rule, Account, AccountType, ExpenseRecord, RecurringRule,
+};
+use crate::error::AppError;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountData {
+    pub name: String,
+    pub account_type: AccountType,
+    pub opening_balance: Decimal,
+    pub currency: String,
+}
+
+/// 账户及其当前余额
+#[derive(Debug, Serialize)]
+pub struct AccountBalance {
+    #[serde(flatten)]
+    pub account: account::Model,
+    pub income_total: Decimal,
+    pub expense_total: Decimal,
+    pub transfer_in_total: Decimal,
+    /// 转出金额加手续费
+    pub transfer_out_total: Decimal,
+    /// 开户余额 + 收入 - 支出 + 转入 - 转出，不含回收站中的记录
+    pub balance: Decimal,
+}
+
+pub async fn get_accounts(db: &DatabaseConnection) -> Result<Vec<account::Model>, AppError> {
+    Ok(Account::find()
+        .order_by_asc(account::Column::Id)
+        .all(db)
+        .await?)
+}
+
+pub async fn get_account_by_id(
+    db: &DatabaseConnection,
+    id: i32,
+) -> Result<Option<account::Model>, AppError> {
+    Ok(Account::find_by_id(id).one(db).await?)
+}
+
+fn check_account_data(validator: &mut Validator, account_data: &AccountData) {
+    let name_length = account_data.name.trim().chars().count();
+    validator.check(
+        name_length > 0 && name_length <= validation::MAX_ACCOUNT_NAME_LENGTH,
+        "name",
+        format!(
+            "Name must be 1-{} characters",
+            validation::MAX_ACCOUNT_NAME_LENGTH
+        ),
+    );
+    validator.check(
+        account_data.opening_balance.normalize().scale() <= 2,
+        "opening_balance",
+        "Amount must have at most 2 decimal places",
+    );
+    validation::check_currency(validator, "currency", &account_data.currency);
+}
+
+pub async fn create_account(
+    db: &DatabaseConnection,
+    account_data: AccountData,
+) -> Result<account::Model, AppError> {
+    let mut validator = Validator::new();
+    check_account_data(&mut validator, &account_data);
+    validator.finish()?;
+
+    let now = chrono::Utc::now().into();
+    let account = account::ActiveModel {
+        name: Set(account_data.name.trim().to_string()),
+        account_type: Set(account_data.account_type),
+        opening_balance: Set(account_data.opening_balance),
+        currency: Set(account_data.currency),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    };
+
+    Ok(account.insert(db).await?)
+}
+
+pub async fn update_account(
+    db: &DatabaseConnection,
+    id: i32,
+    account_data: AccountData,
+) -> Result<account::Model, AppError> {
+    let account = get_account_by_id(db, id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Account", id))?;
+
+    let mut validator = Validator::new();
+    check_account_data(&mut validator, &account_data);
+    validator.finish()?;
+
+    // 已有记录、转账或周期规则的账户不允许修改币种，否则历史金额会被按新币种解读
+    if account_data.currency != account.currency {
+        let used = ExpenseRecord::find()
+            .filter(
+                Condition::any()
+                    .add(expense_record::Column::AccountId.eq(id))
+                    .add(expense_record::Column::ToAccountId.eq(id)),
+            )
+            .count(db)
+            .await?;
+        let rules = RecurringRule::find()
+            .filter(recurring_rule::Column::AccountId.eq(id))
+            .count(db)
+            .await?;
+        if used > 0 || rules > 0 {
+            return Err(AppError::Conflict(format!(
+                "Account {} still has {} records and {} recurring rules, cannot change currency",
+                id, used, rules
+            )));
+        }
+    }
+
+    let mut account: account::ActiveModel = account.into();
+    account.name = Set(account_data.name.trim().to_string());
+    account.account_type = Set(account_data.account_type);
+    account.opening_balance = Set(account_data.opening_balance);
+    account.currency = Set(account_data.currency);
+    account.updated_at = Set(chrono::Utc::now().into());
+
+    Ok(account.update(db).await?)
+}
+
+// 仍有记录、转账（含回收站）或周期规则的账户不允许删除
+pub async fn delete_account(db: &DatabaseConnection, id: i32) -> Result<(), AppError> {
+    let used = ExpenseRecord::find()
+        .filter(
+            Condition::any()
+                .add(expense_record::Column::AccountId.eq(id))
+                .add(expense_record::Column::ToAccountId.eq(id)),
+        )
+        .count(db)
+        .await?;
+    if used > 0 {
+        return Err(AppError::Conflict(format!(
+            "Account {} still has {} records",
+            id, used
+        )));
+    }
+
+    let rules = RecurringRule::find()
+        .filter(recurring_rule::Column::AccountId.eq(id))
+        .count(db)
+        .await?;
+    if rules > 0 {
+        return Err(AppError::Conflict(format!(
+            "Account {} still has {} recurring rules",
+            id, rules
+        )));
+    }
+
+    let result = Account::delete_by_id(id).exec(db).await?;
+    if result.rows_affected == 0 {
+        return Err(AppError::not_found("Account", id));
+    }
+
+    Ok(())
+}
+
+pub async fn get_account_balances(
+    db: &DatabaseConnection,
+) -> Result<Vec<AccountBalance>, AppError> {
+    let accounts = get_accounts(db).await?;
+    let flows = record_service::sum_by_account(db).await?;
+
+    Ok(accounts
+        .into_iter()
+        .map(|account| {
+            let flow = flows.get(&account.id).copied().unwrap_or_default();
+            AccountBalance {
+                balance: account.opening_balance + flow.income_total - flow.expense_total
+                    + flow.transfer_in_total
+                    - flow.transfer_out_total,
+                income_total: flow.income_total,
+                expense_total: flow.expense_total,
+                transfer_in_total: flow.transfer_in_total,
+                transfer_out_total: flow.transfer_out_total,
+                account,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::record_service::ExpenseRecordData;
+    use crate::database::test_connection;
+    use crate::database::test_util::{invalid_fields, record_data};
+    use crate::entities::RecordType;
+
+    fn account_data(name: &str, opening_balance: Decimal) -> AccountData {
+        AccountData {
+            name: name.into(),
+            account_type: AccountType::Cash,
+            opening_balance,
+            currency: "CNY".into(),
+        }
+    }
+
+    async fn record(
+        db: &DatabaseConnection,
+        account_id: i32,
+        record_type: RecordType,
+        category_id: &str,
+        amount: Decimal,
+    ) -> expense_record::Model {
+        record_service::create_record(
+            db,
+            ExpenseRecordData {
+                account_id: Some(account_id),
+                ..record_data(record_type, category_id, amount, None)
+            },
+        )
+        .await
+        .unwrap()
+        .record
+    }
+
+    #[tokio::test]
+    async fn account_validation_reports_each_field() {
+        let db = test_connection().await;
+        let result = create_account(
+            &db,
+            AccountData {
+                currency: "cny".into(),
+                ..account_data(&"a".repeat(31), Decimal::new(1001, 3))
+            },
+        )
+        .await;
+        assert_eq!(
+            invalid_fields(result),
+            ["name", "opening_balance", "currency"]
+        );
+    }
+
+    #[tokio::test]
+    async fn balances_add_income_and_subtract_expense_outside_trash() {
+        let db = test_connection().await;
+        let wallet = create_account(&db, account_data("钱包", Decimal::new(100, 0)))
+            .await
+            .unwrap();
+        let card = create_account(&db, account_data("银行卡", Decimal::ZERO))
+            .await
+            .unwrap();
+
+        record(
+            &db,
+            wallet.id,
+            RecordType::Income,
+            "salary",
+            Decimal::new(50, 0),
+        )
+        .await;
+        record(
+            &db,
+            wallet.id,
+            RecordType::Expense,
+            "food",
+            Decimal::new(2050, 2),
+        )
+        .await;
+        let trashed = record(
+            &db,
+            wallet.id,
+            RecordType::Expense,
+            "food",
+            Decimal::new(30, 0),
+        )
+        .await;
+        record_service::delete_record(&db, trashed.id)
+            .await
+            .unwrap();
+
+        let balances = get_account_balances(&db).await.unwrap();
+        let summary: Vec<_> = balances
+            .iter()
+            .map(|balance| {
+                (
+                    balance.account.id,
+                    balance.income_total,
+                    balance.expense_total,
+                    balance.balance,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    wallet.id,
+                    Decimal::new(50, 0),
+                    Decimal::new(2050, 2),
+                    Decimal::new(12950, 2)
+                ),
+                (card.id, Decimal::ZERO, Decimal::ZERO, Decimal::ZERO),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn used_accounts_cannot_be_deleted_or_change_currency() {
+        let db = test_connection().await;
+        let wallet = create_account(&db, account_data("钱包", Decimal::ZERO))
+            .await
+            .unwrap();
+        let trashed = record(&db, wallet.id, RecordType::Expense, "food", Decimal::ONE).await;
+        record_service::delete_record(&db, trashed.id)
+            .await
+            .unwrap();
+
+        // 回收站中的记录同样占用账户
+        assert!(matches!(
+            delete_account(&db, wallet.id).await,
+            Err(AppError::Conflict(_))
+        ));
+        let result = update_account(
+            &db,
+            wallet.id,
+            AccountData {
+                currency: "USD".into(),
+                ..account_data("钱包", Decimal::ZERO)
+            },
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+
+        record_service::purge_record(&db, trashed.id).await.unwrap();
+        delete_account(&db, wallet.id).await.unwrap();
+        assert!(matches!(
+            delete_account(&db, wallet.id).await,
+            Err(AppError::NotFound { .. })
+        ));
+    }
+}
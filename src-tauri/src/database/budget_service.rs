@@ -0,0 +1,1245 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use crate::database::i18n::{self, Locale};
+use crate::database::record_service::TimeGranularity;
+use crate::database::validation::{self, Validator};
+use crate::database::{category_service, record_service};
+use crate::entities::budget::AlertThresholds;
+use crate::entities::{
+    budget, budget_alert, budget_allocation, Budget, BudgetAlert, BudgetAllocation, BudgetPeriod,
+    CategoryType,
+};
+use crate::error::AppError;
+
+// 新建预算缺省的提醒阈值（百分比）
+pub const DEFAULT_ALERT_THRESHOLDS: [i32; 2] = [80, 100];
+// 每个预算最多设置的提醒阈值个数
+const MAX_ALERT_THRESHOLDS: usize = 5;
+// 提醒阈值允许的最大百分比
+const MAX_ALERT_THRESHOLD: i32 = 500;
+
+// 月、年预算的周期与周起始日无关，结转和分配按周一计算周期即可
+const ROLLOVER_WEEK_START: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BudgetData {
+    pub period: BudgetPeriod,
+    /// 为空表示全部支出
+    pub category_id: Option<String>,
+    /// 本位币金额
+    pub amount: Decimal,
+    /// 结余或超支结转到下一周期，仅月、年预算支持；新建时缺省为 false，修改时缺省保持不变
+    pub rollover: Option<bool>,
+    /// 提醒阈值（百分比），新建时缺省为 80 和 100，修改时缺省保持不变；空数组表示不提醒
+    pub alert_thresholds: Option<Vec<i32>>,
+}
+
+/// 信封模式下某个月份分配给一个分类预算的额度
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnvelopeAllocation {
+    pub budget_id: i32,
+    pub amount: Decimal,
+}
+
+/// 预算在当前周期内的执行情况，金额均为本位币
+#[derive(Debug, Serialize)]
+pub struct BudgetStatus {
+    #[serde(flatten)]
+    pub budget: budget::Model,
+    /// 周期首日（本地日期）
+    pub start_date: NaiveDate,
+    /// 周期最后一天（本地日期）
+    pub end_date: NaiveDate,
+    /// 本周期的额度，信封分配过的月份为分配额
+    pub allocated: Decimal,
+    /// 上一周期结转的结余，超支时为负数
+    pub carried_in: Decimal,
+    /// 本周期可用额度 = 额度 + 结转
+    pub available: Decimal,
+    pub spent: Decimal,
+    /// 超支时为负数
+    pub remaining: Decimal,
+    pub percent_used: Decimal,
+    /// 按周期内已过天数的日均支出推算的周期末总支出
+    pub projected_spend: Decimal,
+    /// 本周期缺少汇率、未计入支出的记录币种
+    pub missing_rates: Vec<String>,
+}
+
+/// 新触发的预算提醒，作为 budget-alert 事件的内容
+#[derive(Debug, Serialize)]
+pub struct BudgetAlertNotice {
+    #[serde(flatten)]
+    pub alert: budget_alert::Model,
+    pub period: BudgetPeriod,
+    pub category_id: Option<String>,
+    /// 分类名称，总预算为空
+    pub category_name: Option<String>,
+    pub spent: Decimal,
+    pub available: Decimal,
+}
+
+/// 信封模式的月度汇总：当月收入分配到各分类预算后剩余的部分
+#[derive(Debug, Serialize)]
+pub struct EnvelopeSummary {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub income: Decimal,
+    pub allocated: Decimal,
+    /// 收入 - 已分配，分配超出收入时为负数
+    pub unallocated: Decimal,
+    pub budgets: Vec<BudgetStatus>,
+    /// 当月缺少汇率、未计入收入的记录币种
+    pub missing_rates: Vec<String>,
+}
+
+fn period_granularity(period: BudgetPeriod) -> TimeGranularity {
+    match period {
+        BudgetPeriod::Weekly => TimeGranularity::Week,
+        BudgetPeriod::Monthly => TimeGranularity::Month,
+        BudgetPeriod::Yearly => TimeGranularity::Year,
+    }
+}
+
+fn supports_rollover(period: BudgetPeriod) -> bool {
+    matches!(period, BudgetPeriod::Monthly | BudgetPeriod::Yearly)
+}
+
+// 去重并从小到大排列
+fn normalize_thresholds(mut thresholds: Vec<i32>) -> AlertThresholds {
+    thresholds.sort_unstable();
+    thresholds.dedup();
+    AlertThresholds(thresholds)
+}
+
+// 预算从创建当天所在的周期开始生效
+fn budget_start_date(budget: &budget::Model) -> NaiveDate {
+    budget.created_at.with_timezone(&chrono::Local).date_naive()
+}
+
+pub async fn get_budgets(db: &DatabaseConnection) -> Result<Vec<budget::Model>, AppError> {
+    Ok(Budget::find()
+        .order_by_asc(budget::Column::Period)
+        .order_by_asc(budget::Column::CategoryId)
+        .all(db)
+        .await?)
+}
+
+pub async fn get_budget_by_id(
+    db: &DatabaseConnection,
+    id: i32,
+) -> Result<Option<budget::Model>, AppError> {
+    Ok(Budget::find_by_id(id).one(db).await?)
+}
+
+// 校验金额和分类，同一周期同一分类只允许一个预算
+async fn validate_budget(
+    db: &DatabaseConnection,
+    budget_data: &BudgetData,
+    current_id: Option<i32>,
+) -> Result<(), AppError> {
+    let mut validator = Validator::new();
+
+    validation::check_amount(&mut validator, "amount", budget_data.amount);
+
+    validator.check(
+        budget_data.rollover != Some(true) || supports_rollover(budget_data.period),
+        "rollover",
+        "Rollover is only supported for monthly and yearly budgets",
+    );
+
+    if let Some(thresholds) = &budget_data.alert_thresholds {
+        validator.check(
+            thresholds.len() <= MAX_ALERT_THRESHOLDS,
+            "alert_thresholds",
+            format!("At most {} thresholds are allowed", MAX_ALERT_THRESHOLDS),
+        );
+        validator.check(
+            thresholds
+                .iter()
+                .all(|threshold| (1..=MAX_ALERT_THRESHOLD).contains(threshold)),
+            "alert_thresholds",
+            format!("Thresholds must be 1-{} percent", MAX_ALERT_THRESHOLD),
+        );
+    }
+
+    if let Some(category_id) = &budget_data.category_id {
+        match category_service::get_category_by_id(db, category_id).await? {
+            Some(category) => validator.check(
+                category.category_type == CategoryType::Expense,
+                "category_id",
+                "Budgets can only use expense categories",
+            ),
+            None => validator.add("category_id", "Category does not exist"),
+        }
+    }
+
+    validator.finish()?;
+
+    let duplicate = Budget::find()
+        .filter(budget::Column::Period.eq(budget_data.period))
+        .filter(match &budget_data.category_id {
+            Some(category_id) => budget::Column::CategoryId.eq(category_id.as_str()),
+            None => budget::Column::CategoryId.is_null(),
+        })
+        .one(db)
+        .await?
+        .filter(|existing| Some(existing.id) != current_id);
+    if let Some(existing) = duplicate {
+        return Err(AppError::Conflict(format!(
+            "Budget {} already covers this period and category",
+            existing.id
+        )));
+    }
+
+    Ok(())
+}
+
+/// 预算从 now 所在的周期开始生效
+pub async fn create_budget(
+    db: &DatabaseConnection,
+    budget_data: BudgetData,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<budget::Model, AppError> {
+    validate_budget(db, &budget_data, None).await?;
+
+    let now = now.into();
+    let budget = budget::ActiveModel {
+        period: Set(budget_data.period),
+        category_id: Set(budget_data.category_id),
+        amount: Set(budget_data.amount),
+        rollover: Set(budget_data.rollover.unwrap_or(false)),
+        alert_thresholds: Set(normalize_thresholds(
+            budget_data
+                .alert_thresholds
+                .unwrap_or_else(|| DEFAULT_ALERT_THRESHOLDS.to_vec()),
+        )),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    };
+
+    Ok(budget.insert(db).await?)
+}
+
+// 先按修改前的额度结束已过去的周期，修改只影响当前及之后的周期
+// 已有周期记录的预算不能再修改周期或分类，否则历史结转会随之改变
+pub async fn update_budget(
+    db: &DatabaseConnection,
+    id: i32,
+    budget_data: BudgetData,
+    today: NaiveDate,
+) -> Result<budget::Model, AppError> {
+    let budget = get_budget_by_id(db, id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Budget", id))?;
+
+    validate_budget(db, &budget_data, Some(id)).await?;
+
+    let txn = db.begin().await?;
+
+    close_budget_periods(&txn, today).await?;
+
+    if budget_data.period != budget.period || budget_data.category_id != budget.category_id {
+        let periods = BudgetAllocation::find()
+            .filter(budget_allocation::Column::BudgetId.eq(id))
+            .count(&txn)
+            .await?;
+        if periods > 0 {
+            return Err(AppError::Conflict(format!(
+                "Budget {} already has {} recorded periods; create a new budget instead",
+                id, periods
+            )));
+        }
+    }
+
+    let rollover = budget_data.rollover.unwrap_or(budget.rollover);
+    let alert_thresholds = budget_data
+        .alert_thresholds
+        .map(normalize_thresholds)
+        .unwrap_or_else(|| budget.alert_thresholds.clone());
+    if supports_rollover(budget.period) {
+        // 已分配但尚未结束的周期跟随新的结转设置
+        let current_start = record_service::bucket_start(
+            today,
+            period_granularity(budget.period),
+            ROLLOVER_WEEK_START,
+        );
+        BudgetAllocation::update_many()
+            .col_expr(budget_allocation::Column::Rollover, Expr::value(rollover))
+            .filter(budget_allocation::Column::BudgetId.eq(id))
+            .filter(budget_allocation::Column::StartDate.gte(current_start))
+            .exec(&txn)
+            .await?;
+    }
+
+    let mut budget: budget::ActiveModel = budget.into();
+    budget.period = Set(budget_data.period);
+    budget.category_id = Set(budget_data.category_id);
+    budget.amount = Set(budget_data.amount);
+    budget.rollover = Set(rollover);
+    budget.alert_thresholds = Set(alert_thresholds);
+    budget.updated_at = Set(chrono::Utc::now().into());
+    let budget = budget.update(&txn).await?;
+
+    txn.commit().await?;
+
+    Ok(budget)
+}
+
+pub async fn delete_budget(db: &DatabaseConnection, id: i32) -> Result<(), AppError> {
+    let txn = db.begin().await?;
+
+    BudgetAllocation::delete_many()
+        .filter(budget_allocation::Column::BudgetId.eq(id))
+        .exec(&txn)
+        .await?;
+
+    BudgetAlert::delete_many()
+        .filter(budget_alert::Column::BudgetId.eq(id))
+        .exec(&txn)
+        .await?;
+
+    let result = Budget::delete_by_id(id).exec(&txn).await?;
+    if result.rows_affected == 0 {
+        return Err(AppError::not_found("Budget", id));
+    }
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// 结束 today 所在周期之前的月、年预算周期：写入额度、结转设置和当期支出的快照，额度和结转取预算当前的值
+/// 已结束的周期保持不变，提前写入的信封分配只补上支出；重复执行结果相同，返回新结束的周期数
+pub async fn close_budget_periods<C: ConnectionTrait>(
+    db: &C,
+    today: NaiveDate,
+) -> Result<u64, AppError> {
+    let budgets = Budget::find()
+        .filter(budget::Column::Period.is_in([BudgetPeriod::Monthly, BudgetPeriod::Yearly]))
+        .all(db)
+        .await?;
+    let parents = category_service::parent_map(db).await?;
+    let closed: HashSet<(i32, NaiveDate)> = BudgetAllocation::find()
+        .select_only()
+        .column(budget_allocation::Column::BudgetId)
+        .column(budget_allocation::Column::StartDate)
+        .filter(budget_allocation::Column::Spent.is_not_null())
+        .into_tuple()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    let now = chrono::Utc::now();
+    let mut periods = Vec::new();
+    for budget in budgets {
+        let granularity = period_granularity(budget.period);
+        let current_start = record_service::bucket_start(today, granularity, ROLLOVER_WEEK_START);
+        let mut start_dates = Vec::new();
+        let mut start_date = record_service::bucket_start(
+            budget_start_date(&budget),
+            granularity,
+            ROLLOVER_WEEK_START,
+        );
+        while start_date < current_start {
+            if !closed.contains(&(budget.id, start_date)) {
+                start_dates.push(start_date);
+            }
+            start_date = record_service::next_bucket_start(start_date, granularity);
+        }
+        let Some(first) = start_dates.first() else {
+            continue;
+        };
+
+        let totals = record_service::sum_expense_by_period_and_category(
+            db,
+            granularity,
+            ROLLOVER_WEEK_START,
+            *first,
+            current_start,
+        )
+        .await?;
+        for start_date in start_dates {
+            let key = record_service::bucket_period(start_date, granularity);
+            let spent: Decimal = totals
+                .iter()
+                .filter(|((period, category_id), _)| {
+                    *period == key && covers_category(&budget, &parents, category_id)
+                })
+                .map(|(_, total)| *total)
+                .sum();
+            periods.push(budget_allocation::ActiveModel {
+                budget_id: Set(budget.id),
+                start_date: Set(start_date),
+                amount: Set(budget.amount),
+                rollover: Set(budget.rollover),
+                spent: Set(Some(spent.round_dp(2))),
+                created_at: Set(now.into()),
+                updated_at: Set(now.into()),
+                ..Default::default()
+            });
+        }
+    }
+
+    if periods.is_empty() {
+        return Ok(0);
+    }
+
+    Ok(BudgetAllocation::insert_many(periods)
+        .on_conflict(
+            OnConflict::columns([
+                budget_allocation::Column::BudgetId,
+                budget_allocation::Column::StartDate,
+            ])
+            .update_columns([
+                budget_allocation::Column::Spent,
+                budget_allocation::Column::UpdatedAt,
+            ])
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?)
+}
+
+// 分类预算包含其所有子分类的支出，总预算包含全部支出
+fn covers_category(
+    budget: &budget::Model,
+    parents: &HashMap<String, Option<String>>,
+    category_id: &str,
+) -> bool {
+    match &budget.category_id {
+        Some(budget_category) => {
+            category_id == budget_category
+                || category_service::ancestors(parents, category_id)
+                    .contains(&budget_category.as_str())
+        }
+        None => true,
+    }
+}
+
+// 依次累计 before 之前各已结束周期的结余，得到结转到 before 所在周期的金额
+// 额度和支出都取周期结束时的快照；不结转的周期把结余清零
+fn carried_balance(periods: &[budget_allocation::Model], before: NaiveDate) -> Decimal {
+    let mut carried = Decimal::ZERO;
+    for period in periods.iter().filter(|period| period.start_date < before) {
+        // 尚未由 close_budget_periods 结束的周期不计入
+        let Some(spent) = period.spent else {
+            continue;
+        };
+        carried = if period.rollover {
+            (period.amount + carried - spent).round_dp(2)
+        } else {
+            Decimal::ZERO
+        };
+    }
+    carried
+}
+
+/// 各预算在 date 所在周期内的执行情况，周预算以 week_start（0 为周日）为一周的第一天
+/// 结转只计入已结束周期的快照，周期由启动时及每天的定时任务通过 close_budget_periods 结束
+pub async fn get_budget_status(
+    db: &DatabaseConnection,
+    date: NaiveDate,
+    week_start: u32,
+) -> Result<Vec<BudgetStatus>, AppError> {
+    if week_start > 6 {
+        return Err(AppError::validation(
+            "week_start",
+            "Week start must be between 0 (Sunday) and 6 (Saturday)",
+        ));
+    }
+
+    let budgets = get_budgets(db).await?;
+    let parents = category_service::parent_map(db).await?;
+    let mut allocations: HashMap<i32, Vec<budget_allocation::Model>> = HashMap::new();
+    for allocation in BudgetAllocation::find()
+        .order_by_asc(budget_allocation::Column::StartDate)
+        .all(db)
+        .await?
+    {
+        allocations
+            .entry(allocation.budget_id)
+            .or_default()
+            .push(allocation);
+    }
+
+    // 同一周期的预算共用一次查询
+    let mut period_totals: HashMap<BudgetPeriod, (HashMap<String, Decimal>, Vec<String>)> =
+        HashMap::new();
+    let mut statuses = Vec::new();
+    for budget in budgets {
+        let granularity = period_granularity(budget.period);
+        let start_date = record_service::bucket_start(date, granularity, week_start);
+        let next_start = record_service::next_bucket_start(start_date, granularity);
+
+        if let Entry::Vacant(entry) = period_totals.entry(budget.period) {
+            entry.insert(
+                record_service::sum_expense_by_category(
+                    db,
+                    record_service::local_midnight_utc(start_date),
+                    record_service::local_midnight_utc(next_start),
+                )
+                .await?,
+            );
+        }
+        let (totals, missing_rates) = &period_totals[&budget.period];
+        let missing_rates = missing_rates.clone();
+        let spent: Decimal = totals
+            .iter()
+            .filter(|(category_id, _)| covers_category(&budget, &parents, category_id))
+            .map(|(_, total)| *total)
+            .sum::<Decimal>()
+            .round_dp(2);
+
+        let periods = allocations.remove(&budget.id).unwrap_or_default();
+        let allocated = periods
+            .iter()
+            .find(|period| period.start_date == start_date)
+            .map_or(budget.amount, |period| period.amount);
+        let carried_in = if supports_rollover(budget.period) {
+            carried_balance(&periods, start_date)
+        } else {
+            Decimal::ZERO
+        };
+        let available = allocated + carried_in;
+
+        let total_days = (next_start - start_date).num_days();
+        let elapsed_days = ((date - start_date).num_days() + 1).clamp(1, total_days);
+        let projected_spend =
+            (spent / Decimal::from(elapsed_days) * Decimal::from(total_days)).round_dp(2);
+        // 可用额度不为正时视为已用完
+        let percent_used = if available > Decimal::ZERO {
+            (spent / available * Decimal::ONE_HUNDRED).round_dp(2)
+        } else {
+            Decimal::ONE_HUNDRED
+        };
+
+        statuses.push(BudgetStatus {
+            start_date,
+            end_date: next_start.pred_opt().unwrap_or(next_start),
+            allocated,
+            carried_in,
+            available,
+            spent,
+            remaining: available - spent,
+            percent_used,
+            projected_spend,
+            missing_rates,
+            budget,
+        });
+    }
+
+    Ok(statuses)
+}
+
+/// 设置 month 所在月份各分类预算的信封分配额，today 之前已结束的月份不能修改
+pub async fn set_budget_allocations(
+    db: &DatabaseConnection,
+    month: NaiveDate,
+    allocations: Vec<EnvelopeAllocation>,
+    today: NaiveDate,
+) -> Result<Vec<budget_allocation::Model>, AppError> {
+    let start_date =
+        record_service::bucket_start(month, TimeGranularity::Month, ROLLOVER_WEEK_START);
+
+    let mut validator = Validator::new();
+    validator.check(
+        start_date
+            >= record_service::bucket_start(today, TimeGranularity::Month, ROLLOVER_WEEK_START),
+        "month",
+        "Cannot allocate a closed month",
+    );
+    let mut budgets = Vec::new();
+    for (index, allocation) in allocations.iter().enumerate() {
+        let field = format!("allocations[{}]", index);
+        validator.check(
+            allocation.amount >= Decimal::ZERO && allocation.amount.normalize().scale() <= 2,
+            &field,
+            "Amount must be at least 0 with at most 2 decimal places",
+        );
+        match get_budget_by_id(db, allocation.budget_id).await? {
+            Some(budget)
+                if budget.period == BudgetPeriod::Monthly && budget.category_id.is_some() =>
+            {
+                budgets.push(budget)
+            }
+            Some(_) => validator.add(&field, "Only monthly category budgets can be allocated"),
+            None => validator.add(&field, "Budget does not exist"),
+        }
+    }
+    validator.finish()?;
+
+    let txn = db.begin().await?;
+
+    close_budget_periods(&txn, today).await?;
+
+    let now = chrono::Utc::now();
+    let mut result = Vec::new();
+    for (budget, allocation) in budgets.into_iter().zip(allocations) {
+        let period = budget_allocation::ActiveModel {
+            budget_id: Set(budget.id),
+            start_date: Set(start_date),
+            amount: Set(allocation.amount),
+            rollover: Set(budget.rollover),
+            created_at: Set(now.into()),
+            updated_at: Set(now.into()),
+            ..Default::default()
+        };
+        BudgetAllocation::insert(period)
+            .on_conflict(
+                OnConflict::columns([
+                    budget_allocation::Column::BudgetId,
+                    budget_allocation::Column::StartDate,
+                ])
+                .update_columns([
+                    budget_allocation::Column::Amount,
+                    budget_allocation::Column::UpdatedAt,
+                ])
+                .to_owned(),
+            )
+            .exec_without_returning(&txn)
+            .await?;
+
+        if let Some(period) = BudgetAllocation::find()
+            .filter(budget_allocation::Column::BudgetId.eq(budget.id))
+            .filter(budget_allocation::Column::StartDate.eq(start_date))
+            .one(&txn)
+            .await?
+        {
+            result.push(period);
+        }
+    }
+
+    txn.commit().await?;
+
+    Ok(result)
+}
+
+/// month 所在月份的信封汇总：收入、分配给各月度分类预算的总额与未分配的余额
+pub async fn get_envelope_summary(
+    db: &DatabaseConnection,
+    month: NaiveDate,
+    today: NaiveDate,
+) -> Result<EnvelopeSummary, AppError> {
+    let start_date =
+        record_service::bucket_start(month, TimeGranularity::Month, ROLLOVER_WEEK_START);
+    let end_date = record_service::next_bucket_start(start_date, TimeGranularity::Month)
+        .pred_opt()
+        .unwrap_or(start_date);
+
+    let series = record_service::get_time_series(
+        db,
+        TimeGranularity::Month,
+        start_date,
+        end_date,
+        ROLLOVER_WEEK_START,
+    )
+    .await?;
+    let income = series
+        .buckets
+        .first()
+        .map(|bucket| bucket.income_total)
+        .unwrap_or_default();
+
+    // 当月按今天推算，其他月份按月末
+    let date = if (start_date..=end_date).contains(&today) {
+        today
+    } else {
+        end_date
+    };
+    let budgets: Vec<BudgetStatus> = get_budget_status(db, date, ROLLOVER_WEEK_START)
+        .await?
+        .into_iter()
+        .filter(|status| {
+            status.budget.period == BudgetPeriod::Monthly && status.budget.category_id.is_some()
+        })
+        .collect();
+    let allocated: Decimal = budgets.iter().map(|status| status.allocated).sum();
+
+    Ok(EnvelopeSummary {
+        start_date,
+        end_date,
+        income,
+        allocated,
+        unallocated: income - allocated,
+        budgets,
+        missing_rates: series.missing_rates,
+    })
+}
+
+/// 检查 date 所在周期内各预算是否达到提醒阈值，记录并返回新触发的提醒
+/// 每个预算每个周期每个阈值只触发一次；一次越过多个阈值时只返回最高的一个
+/// 分类名称按 locale 输出
+pub async fn check_budget_alerts(
+    db: &DatabaseConnection,
+    date: NaiveDate,
+    week_start: u32,
+    locale: Locale,
+) -> Result<Vec<BudgetAlertNotice>, AppError> {
+    let statuses = get_budget_status(db, date, week_start).await?;
+
+    let now = chrono::Utc::now();
+    let mut notices = Vec::new();
+    for status in statuses {
+        // 没有支出时即使可用额度不为正也不提醒
+        if status.spent <= Decimal::ZERO {
+            continue;
+        }
+
+        let mut triggered = None;
+        for &threshold in &status.budget.alert_thresholds.0 {
+            if status.percent_used < Decimal::from(threshold) {
+                break;
+            }
+
+            let alert = budget_alert::ActiveModel {
+                budget_id: Set(status.budget.id),
+                start_date: Set(status.start_date),
+                threshold: Set(threshold),
+                percent_used: Set(status.percent_used),
+                created_at: Set(now.into()),
+                ..Default::default()
+            };
+            let inserted = BudgetAlert::insert(alert)
+                .on_conflict(
+                    OnConflict::columns([
+                        budget_alert::Column::BudgetId,
+                        budget_alert::Column::StartDate,
+                        budget_alert::Column::Threshold,
+                    ])
+                    .do_nothing()
+                    .to_owned(),
+                )
+                .exec_without_returning(db)
+                .await?;
+            if inserted > 0 {
+                triggered = Some(threshold);
+            }
+        }
+
+        let Some(threshold) = triggered else {
+            continue;
+        };
+        let Some(alert) = BudgetAlert::find()
+            .filter(budget_alert::Column::BudgetId.eq(status.budget.id))
+            .filter(budget_alert::Column::StartDate.eq(status.start_date))
+            .filter(budget_alert::Column::Threshold.eq(threshold))
+            .one(db)
+            .await?
+        else {
+            continue;
+        };
+
+        let category_name = match &status.budget.category_id {
+            Some(category_id) => category_service::get_category_by_id(db, category_id)
+                .await?
+                .map(|mut category| {
+                    i18n::localize_category(&mut category, locale);
+                    category.name
+                }),
+            None => None,
+        };
+        notices.push(BudgetAlertNotice {
+            alert,
+            period: status.budget.period,
+            category_id: status.budget.category_id,
+            category_name,
+            spent: status.spent,
+            available: status.available,
+        });
+    }
+
+    Ok(notices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_connection;
+    use crate::database::test_util::{date, record_data, spend, time};
+    use crate::entities::RecordType;
+
+    fn budget_data(period: BudgetPeriod, category_id: Option<&str>, amount: i64) -> BudgetData {
+        BudgetData {
+            period,
+            category_id: category_id.map(String::from),
+            amount: Decimal::new(amount, 0),
+            rollover: None,
+            alert_thresholds: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn budget_validation_reports_each_field_and_rejects_duplicates() {
+        let db = test_connection().await;
+        let now = time("2026-10-01T12:00:00Z");
+
+        let result = create_budget(
+            &db,
+            BudgetData {
+                rollover: Some(true),
+                alert_thresholds: Some(vec![0, 80]),
+                ..budget_data(BudgetPeriod::Weekly, Some("salary"), 0)
+            },
+            now,
+        )
+        .await;
+        match result {
+            Err(AppError::Validation(fields)) => {
+                let fields: Vec<_> = fields.into_iter().map(|field| field.field).collect();
+                assert_eq!(
+                    fields,
+                    ["amount", "rollover", "alert_thresholds", "category_id"]
+                );
+            }
+            other => panic!("expected validation error, got {:?}", other),
+        }
+
+        let budget = create_budget(
+            &db,
+            BudgetData {
+                alert_thresholds: Some(vec![100, 50, 100]),
+                ..budget_data(BudgetPeriod::Monthly, Some("food"), 300)
+            },
+            now,
+        )
+        .await
+        .unwrap();
+        assert_eq!(budget.alert_thresholds.0, [50, 100]);
+
+        let result = create_budget(
+            &db,
+            budget_data(BudgetPeriod::Monthly, Some("food"), 500),
+            now,
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn status_reports_spent_percent_and_projection() {
+        let db = test_connection().await;
+        let now = time("2026-10-01T12:00:00Z");
+        create_budget(&db, budget_data(BudgetPeriod::Monthly, None, 1000), now)
+            .await
+            .unwrap();
+        create_budget(
+            &db,
+            budget_data(BudgetPeriod::Monthly, Some("food"), 300),
+            now,
+        )
+        .await
+        .unwrap();
+
+        spend(&db, "food", Decimal::from(50), Some("2026-10-02T12:00:00Z")).await;
+        // 子分类计入上级分类的预算
+        spend(
+            &db,
+            "snacks",
+            Decimal::from(50),
+            Some("2026-10-05T12:00:00Z"),
+        )
+        .await;
+        spend(
+            &db,
+            "transport",
+            Decimal::from(20),
+            Some("2026-10-06T12:00:00Z"),
+        )
+        .await;
+        // 上个月的支出
+        spend(
+            &db,
+            "food",
+            Decimal::from(999),
+            Some("2026-09-30T12:00:00Z"),
+        )
+        .await;
+
+        let status = get_budget_status(&db, date("2026-10-10"), 1).await.unwrap();
+        let summary: Vec<_> = status
+            .iter()
+            .map(|status| {
+                (
+                    status.budget.category_id.as_deref(),
+                    status.spent,
+                    status.remaining,
+                    status.percent_used,
+                    status.projected_spend,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    None,
+                    Decimal::new(120, 0),
+                    Decimal::new(880, 0),
+                    Decimal::new(12, 0),
+                    Decimal::new(372, 0)
+                ),
+                (
+                    Some("food"),
+                    Decimal::new(100, 0),
+                    Decimal::new(200, 0),
+                    Decimal::new(3333, 2),
+                    Decimal::new(310, 0)
+                ),
+            ]
+        );
+        assert_eq!(status[1].start_date, date("2026-10-01"));
+        assert_eq!(status[1].end_date, date("2026-10-31"));
+    }
+
+    #[tokio::test]
+    async fn weekly_status_follows_week_start() {
+        let db = test_connection().await;
+        create_budget(
+            &db,
+            budget_data(BudgetPeriod::Weekly, None, 100),
+            time("2026-10-01T12:00:00Z"),
+        )
+        .await
+        .unwrap();
+        // 周日和周二
+        spend(&db, "food", Decimal::from(30), Some("2026-10-04T12:00:00Z")).await;
+        spend(&db, "food", Decimal::from(20), Some("2026-10-06T12:00:00Z")).await;
+
+        let status = get_budget_status(&db, date("2026-10-07"), 1).await.unwrap();
+        assert_eq!(status[0].start_date, date("2026-10-05"));
+        assert_eq!(status[0].spent, Decimal::new(20, 0));
+
+        let status = get_budget_status(&db, date("2026-10-07"), 0).await.unwrap();
+        assert_eq!(status[0].start_date, date("2026-10-04"));
+        assert_eq!(status[0].end_date, date("2026-10-10"));
+        assert_eq!(status[0].spent, Decimal::new(50, 0));
+
+        assert!(matches!(
+            get_budget_status(&db, date("2026-10-07"), 7).await,
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    // 8 月初创建的餐饮月预算
+    async fn food_budget(db: &DatabaseConnection, amount: i64, rollover: bool) -> budget::Model {
+        create_budget(
+            db,
+            BudgetData {
+                rollover: Some(rollover),
+                ..budget_data(BudgetPeriod::Monthly, Some("food"), amount)
+            },
+            time("2026-08-05T10:00:00Z"),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn rollover_carries_surplus_and_overspend() {
+        let db = test_connection().await;
+        food_budget(&db, 100, true).await;
+        spend(&db, "food", Decimal::from(80), Some("2026-08-10T10:00:00Z")).await;
+        spend(
+            &db,
+            "food",
+            Decimal::from(150),
+            Some("2026-09-10T10:00:00Z"),
+        )
+        .await;
+        spend(&db, "food", Decimal::from(10), Some("2026-10-02T10:00:00Z")).await;
+
+        // 8 月结余 20 结转到 9 月
+        close_budget_periods(&db, date("2026-09-10")).await.unwrap();
+        let status = get_budget_status(&db, date("2026-09-10"), 1).await.unwrap();
+        assert_eq!(status[0].carried_in, Decimal::new(20, 0));
+        assert_eq!(status[0].available, Decimal::new(120, 0));
+
+        // 9 月超支 30 从 10 月额度中扣除
+        close_budget_periods(&db, date("2026-10-10")).await.unwrap();
+        let status = get_budget_status(&db, date("2026-10-10"), 1).await.unwrap();
+        assert_eq!(status[0].carried_in, Decimal::new(-30, 0));
+        assert_eq!(status[0].available, Decimal::new(70, 0));
+        assert_eq!(status[0].remaining, Decimal::new(60, 0));
+    }
+
+    #[tokio::test]
+    async fn budget_without_rollover_carries_nothing() {
+        let db = test_connection().await;
+        food_budget(&db, 100, false).await;
+        spend(&db, "food", Decimal::from(80), Some("2026-08-10T10:00:00Z")).await;
+
+        close_budget_periods(&db, date("2026-09-10")).await.unwrap();
+        let status = get_budget_status(&db, date("2026-09-10"), 1).await.unwrap();
+        assert_eq!(status[0].carried_in, Decimal::ZERO);
+        assert_eq!(status[0].available, Decimal::new(100, 0));
+    }
+
+    #[tokio::test]
+    async fn status_queries_write_no_snapshots() {
+        let db = test_connection().await;
+        food_budget(&db, 100, true).await;
+
+        get_budget_status(&db, date("2026-10-10"), 1).await.unwrap();
+        assert_eq!(BudgetAllocation::find().count(&db).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn closing_periods_is_idempotent_and_keeps_snapshots() {
+        let db = test_connection().await;
+        let budget = food_budget(&db, 100, true).await;
+        spend(&db, "food", Decimal::from(80), Some("2026-08-10T10:00:00Z")).await;
+
+        // 8、9 月结束，10 月仍在进行
+        assert_eq!(
+            close_budget_periods(&db, date("2026-10-10")).await.unwrap(),
+            2
+        );
+        assert_eq!(
+            close_budget_periods(&db, date("2026-10-10")).await.unwrap(),
+            0
+        );
+
+        // 修改额度不影响已结束周期的快照
+        update_budget(
+            &db,
+            budget.id,
+            budget_data(BudgetPeriod::Monthly, Some("food"), 200),
+            date("2026-10-10"),
+        )
+        .await
+        .unwrap();
+
+        let periods = BudgetAllocation::find()
+            .filter(budget_allocation::Column::BudgetId.eq(budget.id))
+            .order_by_asc(budget_allocation::Column::StartDate)
+            .all(&db)
+            .await
+            .unwrap();
+        assert_eq!(periods.len(), 2);
+        assert_eq!(periods[0].start_date, date("2026-08-01"));
+        assert!(periods
+            .iter()
+            .all(|period| period.amount == Decimal::new(100, 0)));
+
+        // 8 月结余 20，9 月无支出再结余 100
+        let status = get_budget_status(&db, date("2026-10-10"), 1).await.unwrap();
+        assert_eq!(status[0].carried_in, Decimal::new(120, 0));
+        assert_eq!(status[0].allocated, Decimal::new(200, 0));
+
+        // 已有周期记录的预算不能改为其他分类
+        let result = update_budget(
+            &db,
+            budget.id,
+            budget_data(BudgetPeriod::Monthly, Some("transport"), 200),
+            date("2026-10-10"),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn closed_periods_keep_their_spending_after_moves_and_rate_changes() {
+        use crate::database::exchange_rate_service::{self, ExchangeRateData};
+
+        let db = test_connection().await;
+        food_budget(&db, 200, true).await;
+        let usd_rate = |rate| ExchangeRateData {
+            currency: "USD".into(),
+            quote_currency: "CNY".into(),
+            rate_date: date("2026-08-01"),
+            rate: Decimal::from(rate),
+        };
+        exchange_rate_service::set_exchange_rate(&db, usd_rate(7))
+            .await
+            .unwrap();
+        spend(
+            &db,
+            "snacks",
+            Decimal::from(80),
+            Some("2026-08-10T10:00:00Z"),
+        )
+        .await;
+        record_service::create_record(
+            &db,
+            record_service::ExpenseRecordData {
+                currency: Some("USD".into()),
+                ..record_data(
+                    RecordType::Expense,
+                    "food",
+                    Decimal::from(10),
+                    Some("2026-08-12T10:00:00Z"),
+                )
+            },
+        )
+        .await
+        .unwrap();
+
+        // 8 月支出 80 + 10 × 7，结余 50
+        close_budget_periods(&db, date("2026-09-10")).await.unwrap();
+        let status = get_budget_status(&db, date("2026-09-10"), 1).await.unwrap();
+        assert_eq!(status[0].carried_in, Decimal::new(50, 0));
+
+        // 之后把子分类移出、修改当时的汇率，已结束的 8 月不受影响
+        category_service::move_category(&db, "snacks", None)
+            .await
+            .unwrap();
+        exchange_rate_service::set_exchange_rate(&db, usd_rate(8))
+            .await
+            .unwrap();
+        close_budget_periods(&db, date("2026-09-10")).await.unwrap();
+        let status = get_budget_status(&db, date("2026-09-10"), 1).await.unwrap();
+        assert_eq!(status[0].carried_in, Decimal::new(50, 0));
+    }
+
+    #[tokio::test]
+    async fn envelope_allocations_split_monthly_income() {
+        let db = test_connection().await;
+        let food = food_budget(&db, 100, false).await;
+        let weekly = create_budget(
+            &db,
+            budget_data(BudgetPeriod::Weekly, Some("transport"), 50),
+            time("2026-08-05T10:00:00Z"),
+        )
+        .await
+        .unwrap();
+        record_service::create_record(
+            &db,
+            record_data(
+                RecordType::Income,
+                "salary",
+                Decimal::new(1000, 0),
+                Some("2026-10-05T10:00:00Z"),
+            ),
+        )
+        .await
+        .unwrap();
+
+        let today = date("2026-10-10");
+        let result = set_budget_allocations(
+            &db,
+            date("2026-09-15"),
+            vec![EnvelopeAllocation {
+                budget_id: food.id,
+                amount: Decimal::new(400, 0),
+            }],
+            today,
+        )
+        .await;
+        match result {
+            Err(AppError::Validation(fields)) => {
+                let fields: Vec<_> = fields.into_iter().map(|field| field.field).collect();
+                assert_eq!(fields, ["month"]);
+            }
+            other => panic!("expected validation error, got {:?}", other),
+        }
+
+        let result = set_budget_allocations(
+            &db,
+            date("2026-10-15"),
+            vec![
+                EnvelopeAllocation {
+                    budget_id: food.id,
+                    amount: Decimal::new(400, 0),
+                },
+                EnvelopeAllocation {
+                    budget_id: weekly.id,
+                    amount: Decimal::new(100, 0),
+                },
+            ],
+            today,
+        )
+        .await;
+        match result {
+            Err(AppError::Validation(fields)) => {
+                let fields: Vec<_> = fields.into_iter().map(|field| field.field).collect();
+                assert_eq!(fields, ["allocations[1]"]);
+            }
+            other => panic!("expected validation error, got {:?}", other),
+        }
+
+        let periods = set_budget_allocations(
+            &db,
+            date("2026-10-15"),
+            vec![EnvelopeAllocation {
+                budget_id: food.id,
+                amount: Decimal::new(400, 0),
+            }],
+            today,
+        )
+        .await
+        .unwrap();
+        assert_eq!(periods[0].start_date, date("2026-10-01"));
+
+        let summary = get_envelope_summary(&db, date("2026-10-20"), today)
+            .await
+            .unwrap();
+        assert_eq!(summary.income, Decimal::new(1000, 0));
+        assert_eq!(summary.allocated, Decimal::new(400, 0));
+        assert_eq!(summary.unallocated, Decimal::new(600, 0));
+        let budgets: Vec<_> = summary
+            .budgets
+            .iter()
+            .map(|status| status.budget.id)
+            .collect();
+        assert_eq!(budgets, [food.id]);
+    }
+
+    #[tokio::test]
+    async fn alerts_fire_once_per_period_with_the_highest_threshold() {
+        let db = test_connection().await;
+        let budget = create_budget(
+            &db,
+            BudgetData {
+                alert_thresholds: Some(vec![50, 80, 100]),
+                ..budget_data(BudgetPeriod::Monthly, Some("food"), 100)
+            },
+            time("2026-08-05T10:00:00Z"),
+        )
+        .await
+        .unwrap();
+
+        // 一次越过 50 和 80，只返回 80，但两个阈值都已记录
+        spend(&db, "food", Decimal::from(85), Some("2026-09-10T10:00:00Z")).await;
+        let notices = check_budget_alerts(&db, date("2026-09-10"), 1, Locale::En)
+            .await
+            .unwrap();
+        let alerts: Vec<_> = notices
+            .iter()
+            .map(|notice| {
+                (
+                    notice.alert.budget_id,
+                    notice.alert.start_date,
+                    notice.alert.threshold,
+                    notice.category_name.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(alerts, [(budget.id, date("2026-09-01"), 80, Some("Food"))]);
+        assert!(check_budget_alerts(&db, date("2026-09-20"), 1, Locale::En)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(BudgetAlert::find().count(&db).await.unwrap(), 2);
+
+        // 下一周期重新计算
+        spend(&db, "food", Decimal::from(60), Some("2026-10-02T10:00:00Z")).await;
+        let notices = check_budget_alerts(&db, date("2026-10-02"), 1, Locale::ZhCn)
+            .await
+            .unwrap();
+        assert_eq!(notices[0].alert.threshold, 50);
+        assert_eq!(notices[0].alert.start_date, date("2026-10-01"));
+        assert_eq!(notices[0].category_name.as_deref(), Some("餐饮"));
+    }
+}
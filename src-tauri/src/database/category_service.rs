@@ -1,10 +1,19 @@
+use sea_orm::entity::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::{Expr, OnConflict};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
-    QueryFilter, Set,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
-use crate::entities::{category, Category};
+use crate::database::validation::{self, Validator};
+use crate::database::{record_service, settings_service};
+use crate::entities::{
+    budget, category, expense_record, recurring_rule, Budget, Category, CategoryType,
+    ExpenseRecord, RecurringRule,
+};
+use crate::error::AppError;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CategoryData {
@@ -12,36 +21,243 @@ pub struct CategoryData {
     pub name: String,
     pub icon: String,
     pub color: String,
-    pub category_type: String,
+    pub category_type: CategoryType,
+    /// 父分类，仅在新建时使用，之后通过 move_category 调整
+    pub parent_id: Option<String>,
+}
+
+/// 分类树节点
+#[derive(Debug, Serialize)]
+pub struct CategoryNode {
+    #[serde(flatten)]
+    pub category: category::Model,
+    pub children: Vec<CategoryNode>,
+}
+
+/// 删除仍有记录的分类时的处理方式
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum CategoryDeleteMode {
+    /// 有记录时拒绝删除
+    #[default]
+    Block,
+    /// 把记录转移到另一个同类型分类后删除
+    Reassign { target_category_id: String },
+    /// 不删除，只归档
+    Archive,
+}
+
+// 按使用频率排序时统计的天数
+pub const USAGE_SORT_DAYS: i64 = 90;
+
+/// 分类列表的排序方式
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CategorySort {
+    /// 用户自定义顺序
+    #[default]
+    Manual,
+    /// 最近 90 天使用次数从多到少，次数相同时按自定义顺序
+    Usage,
 }
 
+// 默认不返回已归档的分类
 pub async fn get_categories(
     db: &DatabaseConnection,
-    category_type: Option<String>,
-) -> Result<Vec<category::Model>, DbErr> {
-    let mut query = Category::find();
+    category_type: Option<CategoryType>,
+    include_archived: bool,
+    sort: CategorySort,
+) -> Result<Vec<category::Model>, AppError> {
+    let mut query = Category::find()
+        .order_by_asc(category::Column::SortOrder)
+        .order_by_asc(category::Column::Id);
 
     if let Some(cat_type) = category_type {
         query = query.filter(category::Column::CategoryType.eq(cat_type));
     }
 
-    query.all(db).await
+    if !include_archived {
+        query = query.filter(category::Column::Archived.eq(false));
+    }
+
+    let mut categories = query.all(db).await?;
+
+    if let CategorySort::Usage = sort {
+        let since = chrono::Utc::now() - chrono::Duration::days(USAGE_SORT_DAYS);
+        let usage = record_service::count_records_by_category(db, since).await?;
+        // sort_by_key 是稳定排序，次数相同的分类保持自定义顺序
+        categories
+            .sort_by_key(|c| std::cmp::Reverse(usage.get(&c.category_id).copied().unwrap_or(0)));
+    }
+
+    Ok(categories)
+}
+
+// 按父子关系组装分类树，父分类不在结果中的分类作为顶级节点
+pub async fn get_category_tree(
+    db: &DatabaseConnection,
+    category_type: Option<CategoryType>,
+    include_archived: bool,
+) -> Result<Vec<CategoryNode>, AppError> {
+    let categories =
+        get_categories(db, category_type, include_archived, CategorySort::Manual).await?;
+
+    let ids: HashSet<String> = categories.iter().map(|c| c.category_id.clone()).collect();
+    let mut children: HashMap<Option<String>, Vec<category::Model>> = HashMap::new();
+    for category in categories {
+        let parent_id = category.parent_id.clone().filter(|p| ids.contains(p));
+        children.entry(parent_id).or_default().push(category);
+    }
+
+    fn build(
+        parent_id: Option<String>,
+        children: &mut HashMap<Option<String>, Vec<category::Model>>,
+    ) -> Vec<CategoryNode> {
+        children
+            .remove(&parent_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|category| {
+                let nested = build(Some(category.category_id.clone()), children);
+                CategoryNode {
+                    category,
+                    children: nested,
+                }
+            })
+            .collect()
+    }
+
+    Ok(build(None, &mut children))
+}
+
+/// 每个分类 category_id 到其顶级祖先 category_id 的映射，用于统计时把子分类汇总到父分类
+pub async fn get_root_category_ids(
+    db: &DatabaseConnection,
+) -> Result<HashMap<String, String>, AppError> {
+    let parents = parent_map(db).await?;
+
+    Ok(parents
+        .keys()
+        .map(|id| {
+            let root = ancestors(&parents, id).last().copied().unwrap_or(id);
+            (id.clone(), root.to_string())
+        })
+        .collect())
+}
+
+// category_id 到 parent_id 的映射
+pub(crate) async fn parent_map<C: ConnectionTrait>(
+    db: &C,
+) -> Result<HashMap<String, Option<String>>, AppError> {
+    Ok(Category::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|c| (c.category_id, c.parent_id))
+        .collect())
+}
+
+// 由近及远的祖先列表，遇到环时停止
+pub(crate) fn ancestors<'a>(
+    parents: &'a HashMap<String, Option<String>>,
+    category_id: &str,
+) -> Vec<&'a str> {
+    let mut result: Vec<&str> = Vec::new();
+    let mut current = parents.get(category_id).and_then(|p| p.as_deref());
+
+    while let Some(parent_id) = current {
+        if parent_id == category_id || result.contains(&parent_id) {
+            break;
+        }
+        result.push(parent_id);
+        current = parents.get(parent_id).and_then(|p| p.as_deref());
+    }
+
+    result
+}
+
+// 校验父分类存在、类型一致，且不会形成环
+async fn check_parent(
+    db: &DatabaseConnection,
+    validator: &mut Validator,
+    category_id: &str,
+    category_type: CategoryType,
+    parent_id: &str,
+) -> Result<(), AppError> {
+    let Some(parent) = get_category_by_id(db, parent_id).await? else {
+        validator.add("parent_id", "Parent category does not exist");
+        return Ok(());
+    };
+
+    validator.check(
+        parent.category_type == category_type,
+        "parent_id",
+        "Parent category must be of the same type",
+    );
+
+    let parents = parent_map(db).await?;
+    validator.check(
+        parent_id != category_id && !ancestors(&parents, parent_id).contains(&category_id),
+        "parent_id",
+        "A category cannot be moved under itself or its descendants",
+    );
+
+    Ok(())
 }
 
 pub async fn get_category_by_id(
     db: &DatabaseConnection,
     category_id: &str,
-) -> Result<Option<category::Model>, DbErr> {
-    Category::find()
+) -> Result<Option<category::Model>, AppError> {
+    Ok(Category::find()
         .filter(category::Column::CategoryId.eq(category_id))
         .one(db)
-        .await
+        .await?)
+}
+
+// 校验名称、图标和颜色，category_id 仅在新建时校验
+fn check_category_data(validator: &mut Validator, category_data: &CategoryData) {
+    let name_length = category_data.name.trim().chars().count();
+    validator.check(
+        name_length > 0 && name_length <= validation::MAX_CATEGORY_NAME_LENGTH,
+        "name",
+        format!(
+            "Name must be 1-{} characters",
+            validation::MAX_CATEGORY_NAME_LENGTH
+        ),
+    );
+    validation::check_identifier(validator, "icon", &category_data.icon);
+    validation::check_color(validator, "color", &category_data.color);
 }
 
 pub async fn create_category(
     db: &DatabaseConnection,
     category_data: CategoryData,
-) -> Result<category::Model, DbErr> {
+) -> Result<category::Model, AppError> {
+    let mut validator = Validator::new();
+    validation::check_identifier(&mut validator, "category_id", &category_data.category_id);
+    check_category_data(&mut validator, &category_data);
+    if let Some(parent_id) = &category_data.parent_id {
+        check_parent(
+            db,
+            &mut validator,
+            &category_data.category_id,
+            category_data.category_type,
+            parent_id,
+        )
+        .await?;
+    }
+    validator.finish()?;
+
+    // 新分类排在最后
+    let max_sort_order: Option<i32> = Category::find()
+        .select_only()
+        .column_as(category::Column::SortOrder.max(), "max_sort_order")
+        .into_tuple()
+        .one(db)
+        .await?
+        .flatten();
+
     let now = chrono::Utc::now().into();
     let category = category::ActiveModel {
         category_id: Set(category_data.category_id),
@@ -49,130 +265,1036 @@ pub async fn create_category(
         icon: Set(category_data.icon),
         color: Set(category_data.color),
         category_type: Set(category_data.category_type),
+        parent_id: Set(category_data.parent_id),
+        archived: Set(false),
+        sort_order: Set(max_sort_order.map_or(0, |max| max + 1)),
         created_at: Set(now),
         updated_at: Set(now),
         ..Default::default()
     };
 
-    category.insert(db).await
+    Ok(category.insert(db).await?)
 }
 
 pub async fn update_category(
     db: &DatabaseConnection,
     category_id: &str,
     category_data: CategoryData,
-) -> Result<category::Model, DbErr> {
+) -> Result<category::Model, AppError> {
     let category: Option<category::Model> = Category::find()
         .filter(category::Column::CategoryId.eq(category_id))
         .one(db)
         .await?;
 
     if let Some(category) = category {
+        let mut validator = Validator::new();
+        check_category_data(&mut validator, &category_data);
+
+        // 已有记录时不允许把分类改成另一种类型
+        if category.category_type != category_data.category_type {
+            let used = ExpenseRecord::find()
+                .filter(expense_record::Column::CategoryId.eq(category_id))
+                .count(db)
+                .await?;
+            validator.check(
+                used == 0,
+                "category_type",
+                format!("Category type cannot change while {} records use it", used),
+            );
+
+            let has_children = Category::find()
+                .filter(category::Column::ParentId.eq(category_id))
+                .count(db)
+                .await?
+                > 0;
+            validator.check(
+                category.parent_id.is_none() && !has_children,
+                "category_type",
+                "Category type cannot change while it has a parent or children",
+            );
+        }
+        validator.finish()?;
+
+        // 周期规则和预算按原类型引用该分类，改类型前需先处理
+        if category.category_type != category_data.category_type {
+            let rules = RecurringRule::find()
+                .filter(recurring_rule::Column::CategoryId.eq(category_id))
+                .count(db)
+                .await?;
+            if rules > 0 {
+                return Err(AppError::Conflict(format!(
+                    "Category {} still has {} recurring rules",
+                    category_id, rules
+                )));
+            }
+
+            let budgets = Budget::find()
+                .filter(budget::Column::CategoryId.eq(category_id))
+                .count(db)
+                .await?;
+            if budgets > 0 {
+                return Err(AppError::Conflict(format!(
+                    "Category {} still has {} budgets",
+                    category_id, budgets
+                )));
+            }
+        }
+
+        // 改名后不再使用默认名称的翻译
+        let renamed = category.name != category_data.name;
+
         let mut category: category::ActiveModel = category.into();
+        if renamed {
+            category.name_key = Set(None);
+        }
         category.name = Set(category_data.name);
         category.icon = Set(category_data.icon);
         category.color = Set(category_data.color);
         category.category_type = Set(category_data.category_type);
         category.updated_at = Set(chrono::Utc::now().into());
 
-        category.update(db).await
+        Ok(category.update(db).await?)
     } else {
-        Err(DbErr::RecordNotFound("Category not found".into()))
+        Err(AppError::not_found("Category", category_id))
     }
 }
 
-pub async fn delete_category(db: &DatabaseConnection, category_id: &str) -> Result<(), DbErr> {
-    Category::delete_many()
-        .filter(category::Column::CategoryId.eq(category_id))
-        .exec(db)
+// 移动到 parent_id 之下，None 表示移为顶级分类
+pub async fn move_category(
+    db: &DatabaseConnection,
+    category_id: &str,
+    parent_id: Option<String>,
+) -> Result<category::Model, AppError> {
+    let category = get_category_by_id(db, category_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Category", category_id))?;
+
+    if let Some(parent_id) = &parent_id {
+        let mut validator = Validator::new();
+        check_parent(
+            db,
+            &mut validator,
+            category_id,
+            category.category_type,
+            parent_id,
+        )
+        .await?;
+        validator.finish()?;
+    }
+
+    let mut category: category::ActiveModel = category.into();
+    category.parent_id = Set(parent_id);
+    category.updated_at = Set(chrono::Utc::now().into());
+
+    Ok(category.update(db).await?)
+}
+
+/// 按给定顺序重排分类，未列出的分类保持原有相对顺序排在后面
+pub async fn reorder_categories(
+    db: &DatabaseConnection,
+    category_ids: Vec<String>,
+) -> Result<(), AppError> {
+    let categories = get_categories(db, None, true, CategorySort::Manual).await?;
+
+    let mut validator = Validator::new();
+    let mut seen = HashSet::new();
+    for category_id in &category_ids {
+        if !seen.insert(category_id.as_str()) {
+            validator.add(
+                "category_ids",
+                format!("Duplicate category: {}", category_id),
+            );
+        } else if !categories.iter().any(|c| &c.category_id == category_id) {
+            validator.add(
+                "category_ids",
+                format!("Category does not exist: {}", category_id),
+            );
+        }
+    }
+    validator.finish()?;
+
+    let rest = categories
+        .into_iter()
+        .map(|c| c.category_id)
+        .filter(|id| !seen.contains(id.as_str()));
+    let ordered: Vec<String> = category_ids.iter().cloned().chain(rest).collect();
+
+    let txn = db.begin().await?;
+    for (index, category_id) in ordered.iter().enumerate() {
+        Category::update_many()
+            .col_expr(category::Column::SortOrder, Expr::value(index as i32))
+            .filter(category::Column::CategoryId.eq(category_id))
+            .exec(&txn)
+            .await?;
+    }
+    txn.commit().await?;
+
+    Ok(())
+}
+
+pub async fn delete_category(
+    db: &DatabaseConnection,
+    category_id: &str,
+    mode: CategoryDeleteMode,
+) -> Result<(), AppError> {
+    let category = get_category_by_id(db, category_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Category", category_id))?;
+
+    // 回收站中的记录同样引用该分类
+    let used = ExpenseRecord::find()
+        .filter(expense_record::Column::CategoryId.eq(category_id))
+        .count(db)
         .await?;
 
+    match mode {
+        CategoryDeleteMode::Block => {
+            if used > 0 {
+                return Err(AppError::Conflict(format!(
+                    "Category {} still has {} records",
+                    category_id, used
+                )));
+            }
+
+            let rules = RecurringRule::find()
+                .filter(recurring_rule::Column::CategoryId.eq(category_id))
+                .count(db)
+                .await?;
+            if rules > 0 {
+                return Err(AppError::Conflict(format!(
+                    "Category {} still has {} recurring rules",
+                    category_id, rules
+                )));
+            }
+
+            let budgets = Budget::find()
+                .filter(budget::Column::CategoryId.eq(category_id))
+                .count(db)
+                .await?;
+            if budgets > 0 {
+                return Err(AppError::Conflict(format!(
+                    "Category {} still has {} budgets",
+                    category_id, budgets
+                )));
+            }
+
+            let children = Category::find()
+                .filter(category::Column::ParentId.eq(category_id))
+                .count(db)
+                .await?;
+            if children > 0 {
+                return Err(AppError::Conflict(format!(
+                    "Category {} still has {} subcategories",
+                    category_id, children
+                )));
+            }
+
+            Category::delete_by_id(category.id).exec(db).await?;
+        }
+        CategoryDeleteMode::Reassign { target_category_id } => {
+            merge_categories(db, category_id, &target_category_id, false).await?;
+        }
+        CategoryDeleteMode::Archive => {
+            set_category_archived(db, category_id, true).await?;
+        }
+    }
+
     Ok(())
 }
 
-// 初始化默认分类数据
-pub async fn initialize_default_categories(db: &DatabaseConnection) -> Result<(), DbErr> {
-    // 检查是否已经有数据
-    let count = Category::find().count(db).await?;
-    if count > 0 {
+/// 把 source 分类的全部记录（含回收站）、周期规则、预算和子分类转到 target 分类，然后归档或删除 source
+/// 返回转移的记录数
+pub async fn merge_categories(
+    db: &DatabaseConnection,
+    source_category_id: &str,
+    target_category_id: &str,
+    archive_source: bool,
+) -> Result<u64, AppError> {
+    let source = get_category_by_id(db, source_category_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Category", source_category_id))?;
+    let target = get_category_by_id(db, target_category_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Category", target_category_id))?;
+
+    let mut validator = Validator::new();
+    validator.check(
+        target.id != source.id,
+        "target_category_id",
+        "Target category must differ from the source category",
+    );
+    validator.check(
+        target.category_type == source.category_type,
+        "target_category_id",
+        "Target category must be of the same type",
+    );
+    validator.check(
+        !target.archived,
+        "target_category_id",
+        "Target category is archived",
+    );
+    let parents = parent_map(db).await?;
+    validator.check(
+        !ancestors(&parents, target_category_id).contains(&source_category_id),
+        "target_category_id",
+        "Target category cannot be a subcategory of the source category",
+    );
+    // 同一周期的预算无法合并
+    let budget_periods = |category_id: &str| {
+        Budget::find()
+            .filter(budget::Column::CategoryId.eq(category_id))
+            .all(db)
+    };
+    let target_periods: Vec<_> = budget_periods(target_category_id)
+        .await?
+        .into_iter()
+        .map(|b| b.period)
+        .collect();
+    validator.check(
+        !budget_periods(source_category_id)
+            .await?
+            .iter()
+            .any(|b| target_periods.contains(&b.period)),
+        "target_category_id",
+        "Both categories have a budget for the same period",
+    );
+    validator.finish()?;
+
+    let txn = db.begin().await?;
+
+    Category::update_many()
+        .col_expr(
+            category::Column::ParentId,
+            Expr::value(target.category_id.clone()),
+        )
+        .filter(category::Column::ParentId.eq(source_category_id))
+        .exec(&txn)
+        .await?;
+
+    Budget::update_many()
+        .col_expr(
+            budget::Column::CategoryId,
+            Expr::value(target.category_id.clone()),
+        )
+        .filter(budget::Column::CategoryId.eq(source_category_id))
+        .exec(&txn)
+        .await?;
+
+    RecurringRule::update_many()
+        .col_expr(
+            recurring_rule::Column::CategoryId,
+            Expr::value(target.category_id.clone()),
+        )
+        .filter(recurring_rule::Column::CategoryId.eq(source_category_id))
+        .exec(&txn)
+        .await?;
+
+    let moved = ExpenseRecord::update_many()
+        .col_expr(
+            expense_record::Column::CategoryId,
+            Expr::value(target.category_id),
+        )
+        .filter(expense_record::Column::CategoryId.eq(source_category_id))
+        .exec(&txn)
+        .await?
+        .rows_affected;
+
+    if archive_source {
+        let mut source: category::ActiveModel = source.into();
+        source.archived = Set(true);
+        source.updated_at = Set(chrono::Utc::now().into());
+        source.update(&txn).await?;
+    } else {
+        Category::delete_by_id(source.id).exec(&txn).await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(moved)
+}
+
+pub async fn set_category_archived(
+    db: &DatabaseConnection,
+    category_id: &str,
+    archived: bool,
+) -> Result<category::Model, AppError> {
+    let category = get_category_by_id(db, category_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Category", category_id))?;
+
+    let mut category: category::ActiveModel = category.into();
+    category.archived = Set(archived);
+    category.updated_at = Set(chrono::Utc::now().into());
+
+    Ok(category.update(db).await?)
+}
+
+// 当前内置默认分类的版本，新增默认分类时加一，并把新条目的 since 设为新版本
+pub const DEFAULT_CATEGORIES_VERSION: u32 = 1;
+
+/// 内置默认分类
+struct DefaultCategory {
+    category_id: &'static str,
+    name: &'static str,
+    icon: &'static str,
+    color: &'static str,
+    category_type: CategoryType,
+    parent_id: Option<&'static str>,
+    /// 首次加入默认分类的版本
+    since: u32,
+}
+
+impl DefaultCategory {
+    const fn new(
+        category_type: CategoryType,
+        category_id: &'static str,
+        name: &'static str,
+        icon: &'static str,
+        color: &'static str,
+    ) -> Self {
+        Self {
+            category_id,
+            name,
+            icon,
+            color,
+            category_type,
+            parent_id: None,
+            since: 1,
+        }
+    }
+
+    const fn expense(
+        category_id: &'static str,
+        name: &'static str,
+        icon: &'static str,
+        color: &'static str,
+    ) -> Self {
+        Self::new(CategoryType::Expense, category_id, name, icon, color)
+    }
+
+    const fn income(
+        category_id: &'static str,
+        name: &'static str,
+        icon: &'static str,
+        color: &'static str,
+    ) -> Self {
+        Self::new(CategoryType::Income, category_id, name, icon, color)
+    }
+
+    const fn under(mut self, parent_id: &'static str) -> Self {
+        self.parent_id = Some(parent_id);
+        self
+    }
+}
+
+// 支出分类在前，收入分类在后；蔬菜、水果、零食归在餐饮之下
+const DEFAULT_CATEGORIES: &[DefaultCategory] = &[
+    DefaultCategory::expense("food", "餐饮", "restaurant", "orange"),
+    DefaultCategory::expense("shopping", "购物", "shopping_cart", "pink"),
+    DefaultCategory::expense("daily", "日用", "home", "blue"),
+    DefaultCategory::expense("transport", "交通", "directions_car", "green"),
+    DefaultCategory::expense("vegetables", "蔬菜", "eco", "green").under("food"),
+    DefaultCategory::expense("fruits", "水果", "apple", "red").under("food"),
+    DefaultCategory::expense("snacks", "零食", "cookie", "brown").under("food"),
+    DefaultCategory::expense("sports", "运动", "fitness_center", "blue"),
+    DefaultCategory::expense("entertainment", "娱乐", "movie", "purple"),
+    DefaultCategory::expense("communication", "通讯", "phone", "blue"),
+    DefaultCategory::expense("clothing", "服饰", "checkroom", "pink"),
+    DefaultCategory::expense("beauty", "美容", "face", "pink"),
+    DefaultCategory::expense("housing", "住房", "house", "brown"),
+    DefaultCategory::expense("household", "居家", "chair", "grey"),
+    DefaultCategory::expense("children", "孩子", "child_care", "yellow"),
+    DefaultCategory::expense("elderly", "长辈", "elderly", "grey"),
+    DefaultCategory::expense("social", "社交", "group", "blue"),
+    DefaultCategory::expense("travel", "旅行", "flight", "cyan"),
+    DefaultCategory::expense("tobacco", "烟酒", "local_bar", "red"),
+    DefaultCategory::expense("digital", "数码", "devices", "blue"),
+    DefaultCategory::expense("car", "汽车", "directions_car", "grey"),
+    DefaultCategory::expense("medical", "医疗", "local_hospital", "red"),
+    DefaultCategory::expense("books", "书籍", "book", "brown"),
+    DefaultCategory::expense("study", "学习", "school", "blue"),
+    DefaultCategory::expense("pets", "宠物", "pets", "orange"),
+    DefaultCategory::expense("gift_money", "礼金", "card_giftcard", "red"),
+    DefaultCategory::expense("gifts", "礼物", "redeem", "pink"),
+    DefaultCategory::expense("office", "办公", "work", "grey"),
+    DefaultCategory::expense("repair", "维修", "build", "orange"),
+    DefaultCategory::expense("donation", "捐赠", "volunteer_activism", "green"),
+    DefaultCategory::expense("lottery", "彩票", "casino", "yellow"),
+    DefaultCategory::expense("friends", "亲友", "family_restroom", "blue"),
+    DefaultCategory::expense("express", "快递", "local_shipping", "brown"),
+    DefaultCategory::expense("settings", "设置", "settings", "grey"),
+    DefaultCategory::income("salary", "工资", "work", "green"),
+    DefaultCategory::income("bonus", "奖金", "star", "yellow"),
+    DefaultCategory::income("investment", "投资", "trending_up", "blue"),
+    DefaultCategory::income("part_time", "兼职", "schedule", "orange"),
+    DefaultCategory::income("gift", "礼金", "card_giftcard", "red"),
+    DefaultCategory::income("other", "其他", "more_horiz", "grey"),
+];
+
+// 按版本写入默认分类：只补充比已记录版本更新的条目，已存在的 category_id 原样保留，
+// 因此记录版本之后用户改过名或删除的默认分类不会被覆盖或恢复。整个过程在一个事务中完成
+pub async fn initialize_default_categories(db: &DatabaseConnection) -> Result<(), AppError> {
+    let txn = db.begin().await?;
+
+    let stored_version =
+        settings_service::get_setting(&txn, settings_service::DEFAULT_CATEGORIES_VERSION_KEY)
+            .await?
+            .map(|version| {
+                version.parse::<u32>().map_err(|e| {
+                    AppError::Database(format!("Invalid default categories version: {}", e))
+                })
+            })
+            .transpose()?;
+
+    if stored_version.is_some_and(|version| version >= DEFAULT_CATEGORIES_VERSION) {
         return Ok(());
     }
 
-    // 支出分类
-    let expense_categories = vec![
-        ("food", "餐饮", "restaurant", "orange"),
-        ("shopping", "购物", "shopping_cart", "pink"),
-        ("daily", "日用", "home", "blue"),
-        ("transport", "交通", "directions_car", "green"),
-        ("vegetables", "蔬菜", "eco", "green"),
-        ("fruits", "水果", "apple", "red"),
-        ("snacks", "零食", "cookie", "brown"),
-        ("sports", "运动", "fitness_center", "blue"),
-        ("entertainment", "娱乐", "movie", "purple"),
-        ("communication", "通讯", "phone", "blue"),
-        ("clothing", "服饰", "checkroom", "pink"),
-        ("beauty", "美容", "face", "pink"),
-        ("housing", "住房", "house", "brown"),
-        ("household", "居家", "chair", "grey"),
-        ("children", "孩子", "child_care", "yellow"),
-        ("elderly", "长辈", "elderly", "grey"),
-        ("social", "社交", "group", "blue"),
-        ("travel", "旅行", "flight", "cyan"),
-        ("tobacco", "烟酒", "local_bar", "red"),
-        ("digital", "数码", "devices", "blue"),
-        ("car", "汽车", "directions_car", "grey"),
-        ("medical", "医疗", "local_hospital", "red"),
-        ("books", "书籍", "book", "brown"),
-        ("study", "学习", "school", "blue"),
-        ("pets", "宠物", "pets", "orange"),
-        ("gift_money", "礼金", "card_giftcard", "red"),
-        ("gifts", "礼物", "redeem", "pink"),
-        ("office", "办公", "work", "grey"),
-        ("repair", "维修", "build", "orange"),
-        ("donation", "捐赠", "volunteer_activism", "green"),
-        ("lottery", "彩票", "casino", "yellow"),
-        ("friends", "亲友", "family_restroom", "blue"),
-        ("express", "快递", "local_shipping", "brown"),
-        ("settings", "设置", "settings", "grey"),
-    ];
-
-    for (id, name, icon, color) in expense_categories {
-        create_category(
-            db,
+    // 没有版本号时为首次安装，或是引入版本号之前的数据库，其默认分类可能只写入了一部分；
+    // 两种情况都逐个补上缺少的默认分类，已存在的保持不变
+    let stored_version = stored_version.unwrap_or(0);
+
+    let max_sort_order: Option<i32> = Category::find()
+        .select_only()
+        .column_as(category::Column::SortOrder.max(), "max_sort_order")
+        .into_tuple()
+        .one(&txn)
+        .await?
+        .flatten();
+    let mut sort_order = max_sort_order.map_or(0, |max| max + 1);
+
+    let now: DateTimeWithTimeZone = chrono::Utc::now().into();
+    for default in DEFAULT_CATEGORIES
+        .iter()
+        .filter(|d| d.since > stored_version)
+    {
+        // 父分类被用户删除时作为顶级分类写入
+        let parent_id = match default.parent_id {
+            Some(parent_id) => Category::find()
+                .filter(category::Column::CategoryId.eq(parent_id))
+                .one(&txn)
+                .await?
+                .map(|parent| parent.category_id),
+            None => None,
+        };
+
+        let category = category::ActiveModel {
+            category_id: Set(default.category_id.to_string()),
+            name: Set(default.name.to_string()),
+            name_key: Set(Some(default.category_id.to_string())),
+            icon: Set(default.icon.to_string()),
+            color: Set(default.color.to_string()),
+            category_type: Set(default.category_type),
+            parent_id: Set(parent_id),
+            archived: Set(false),
+            sort_order: Set(sort_order),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+
+        let inserted = Category::insert(category)
+            .on_conflict(
+                OnConflict::column(category::Column::CategoryId)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&txn)
+            .await?;
+        if inserted > 0 {
+            sort_order += 1;
+        }
+    }
+
+    settings_service::set_setting(
+        &txn,
+        settings_service::DEFAULT_CATEGORIES_VERSION_KEY,
+        &DEFAULT_CATEGORIES_VERSION.to_string(),
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_connection;
+    use crate::database::test_util::{invalid_fields, record_data, spend};
+    use crate::entities::AppSetting;
+
+    fn category_data(category_id: &str, name: &str, category_type: CategoryType) -> CategoryData {
+        CategoryData {
+            category_id: category_id.into(),
+            name: name.into(),
+            icon: "star".into(),
+            color: "blue".into(),
+            category_type,
+            parent_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn category_validation_reports_each_field() {
+        let db = test_connection().await;
+
+        let result = create_category(
+            &db,
             CategoryData {
-                category_id: id.to_string(),
-                name: name.to_string(),
-                icon: icon.to_string(),
-                color: color.to_string(),
-                category_type: "expense".to_string(),
+                icon: "Star Icon".into(),
+                color: "#12345".into(),
+                ..category_data(
+                    "Coffee",
+                    &"咖".repeat(validation::MAX_CATEGORY_NAME_LENGTH + 1),
+                    CategoryType::Expense,
+                )
             },
         )
-        .await?;
+        .await;
+        assert_eq!(
+            invalid_fields(result),
+            ["category_id", "name", "icon", "color"]
+        );
+
+        let result = create_category(
+            &db,
+            CategoryData {
+                color: "#A1B2C3".into(),
+                ..category_data("coffee", "咖啡", CategoryType::Expense)
+            },
+        )
+        .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn type_cannot_change_while_a_recurring_rule_uses_the_category() {
+        let db = test_connection().await;
+        create_category(&db, category_data("rent", "房租", CategoryType::Expense))
+            .await
+            .unwrap();
+        crate::database::recurring_service::create_recurring_rule(
+            &db,
+            crate::database::recurring_service::RecurringRuleData {
+                record_type: crate::entities::RecordType::Expense,
+                category_id: "rent".into(),
+                account_id: None,
+                amount: rust_decimal::Decimal::new(3000, 0),
+                currency: None,
+                note: None,
+                frequency: crate::entities::RecurrenceFrequency::Monthly,
+                interval_days: None,
+                day_of_month: None,
+                start_date: crate::database::test_util::date("2099-01-01"),
+                end_date: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let result = update_category(
+            &db,
+            "rent",
+            category_data("rent", "房租", CategoryType::Income),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
     }
 
-    // 收入分类
-    let income_categories = vec![
-        ("salary", "工资", "work", "green"),
-        ("bonus", "奖金", "star", "yellow"),
-        ("investment", "投资", "trending_up", "blue"),
-        ("part_time", "兼职", "schedule", "orange"),
-        ("gift", "礼金", "card_giftcard", "red"),
-        ("other", "其他", "more_horiz", "grey"),
-    ];
+    #[tokio::test]
+    async fn type_cannot_change_while_a_budget_uses_the_category() {
+        let db = test_connection().await;
+        create_category(&db, category_data("rent", "房租", CategoryType::Expense))
+            .await
+            .unwrap();
+        crate::database::budget_service::create_budget(
+            &db,
+            crate::database::budget_service::BudgetData {
+                period: crate::entities::BudgetPeriod::Monthly,
+                category_id: Some("rent".into()),
+                amount: rust_decimal::Decimal::new(3000, 0),
+                rollover: None,
+                alert_thresholds: None,
+            },
+            chrono::Utc::now(),
+        )
+        .await
+        .unwrap();
+
+        let result = update_category(
+            &db,
+            "rent",
+            category_data("rent", "房租", CategoryType::Income),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+
+        // 名称等其他字段仍可修改
+        let result = update_category(
+            &db,
+            "rent",
+            category_data("rent", "租金", CategoryType::Expense),
+        )
+        .await;
+        assert_eq!(result.unwrap().name, "租金");
+    }
 
-    for (id, name, icon, color) in income_categories {
+    async fn record_category(db: &DatabaseConnection, id: i32) -> Option<String> {
+        ExpenseRecord::find_by_id(id)
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap()
+            .category_id
+    }
+
+    #[tokio::test]
+    async fn delete_blocks_reassigns_or_archives_used_categories() {
+        let db = test_connection().await;
+        create_category(&db, category_data("coffee", "咖啡", CategoryType::Expense))
+            .await
+            .unwrap();
+        let record_id = spend(&db, "coffee", rust_decimal::Decimal::TEN, None)
+            .await
+            .id;
+
+        let result = delete_category(&db, "coffee", CategoryDeleteMode::Block).await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+
+        delete_category(&db, "coffee", CategoryDeleteMode::Archive)
+            .await
+            .unwrap();
+        let coffee = get_category_by_id(&db, "coffee").await.unwrap().unwrap();
+        assert!(coffee.archived);
+        let visible = get_categories(&db, None, false, CategorySort::Manual)
+            .await
+            .unwrap();
+        assert!(visible.iter().all(|c| c.category_id != "coffee"));
+
+        // 只能转移到同类型的分类
+        let result = delete_category(
+            &db,
+            "coffee",
+            CategoryDeleteMode::Reassign {
+                target_category_id: "salary".into(),
+            },
+        )
+        .await;
+        assert_eq!(invalid_fields(result), ["target_category_id"]);
+
+        delete_category(
+            &db,
+            "coffee",
+            CategoryDeleteMode::Reassign {
+                target_category_id: "food".into(),
+            },
+        )
+        .await
+        .unwrap();
+        assert!(get_category_by_id(&db, "coffee").await.unwrap().is_none());
+        assert_eq!(
+            record_category(&db, record_id).await.as_deref(),
+            Some("food")
+        );
+
+        // 没有记录的分类直接删除
+        delete_category(&db, "lottery", CategoryDeleteMode::Block)
+            .await
+            .unwrap();
+        assert!(get_category_by_id(&db, "lottery").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn merge_moves_records_and_children_to_target() {
+        let db = test_connection().await;
         create_category(
-            db,
+            &db,
+            category_data("gift_dup", "礼金2", CategoryType::Expense),
+        )
+        .await
+        .unwrap();
+        create_category(
+            &db,
             CategoryData {
-                category_id: id.to_string(),
-                name: name.to_string(),
-                icon: icon.to_string(),
-                color: color.to_string(),
-                category_type: "income".to_string(),
+                parent_id: Some("gift_dup".into()),
+                ..category_data("red_packet", "红包", CategoryType::Expense)
             },
         )
-        .await?;
+        .await
+        .unwrap();
+        let first = spend(&db, "gift_dup", rust_decimal::Decimal::TEN, None)
+            .await
+            .id;
+        let second = spend(&db, "gift_dup", rust_decimal::Decimal::TEN, None)
+            .await
+            .id;
+
+        assert_eq!(
+            invalid_fields(merge_categories(&db, "gift_dup", "salary", false).await),
+            ["target_category_id"]
+        );
+        assert_eq!(
+            invalid_fields(merge_categories(&db, "gift_dup", "red_packet", false).await),
+            ["target_category_id"]
+        );
+
+        let moved = merge_categories(&db, "gift_dup", "gift_money", true)
+            .await
+            .unwrap();
+        assert_eq!(moved, 2);
+        for id in [first, second] {
+            assert_eq!(
+                record_category(&db, id).await.as_deref(),
+                Some("gift_money")
+            );
+        }
+        let red_packet = get_category_by_id(&db, "red_packet")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(red_packet.parent_id.as_deref(), Some("gift_money"));
+        assert!(
+            get_category_by_id(&db, "gift_dup")
+                .await
+                .unwrap()
+                .unwrap()
+                .archived
+        );
+
+        // 不归档时删除来源分类
+        assert_eq!(
+            merge_categories(&db, "red_packet", "gift_money", false)
+                .await
+                .unwrap(),
+            0
+        );
+        assert!(get_category_by_id(&db, "red_packet")
+            .await
+            .unwrap()
+            .is_none());
     }
 
-    Ok(())
+    fn child_ids(tree: &[CategoryNode], category_id: &str) -> Vec<String> {
+        tree.iter()
+            .find(|node| node.category.category_id == category_id)
+            .map(|node| {
+                node.children
+                    .iter()
+                    .map(|child| child.category.category_id.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn tree_follows_moves_and_rejects_cycles() {
+        let db = test_connection().await;
+        let tree = get_category_tree(&db, Some(CategoryType::Expense), false)
+            .await
+            .unwrap();
+        assert_eq!(child_ids(&tree, "food"), ["vegetables", "fruits", "snacks"]);
+        assert!(tree
+            .iter()
+            .all(|node| node.category.category_id != "fruits"));
+
+        assert_eq!(
+            invalid_fields(move_category(&db, "food", Some("fruits".into())).await),
+            ["parent_id"]
+        );
+        assert_eq!(
+            invalid_fields(move_category(&db, "snacks", Some("salary".into())).await),
+            ["parent_id"]
+        );
+
+        move_category(&db, "snacks", None).await.unwrap();
+        move_category(&db, "fruits", Some("vegetables".into()))
+            .await
+            .unwrap();
+        let tree = get_category_tree(&db, Some(CategoryType::Expense), false)
+            .await
+            .unwrap();
+        assert_eq!(child_ids(&tree, "food"), ["vegetables"]);
+        assert!(tree
+            .iter()
+            .any(|node| node.category.category_id == "snacks"));
+        assert_eq!(child_ids(&tree[0].children, "vegetables"), ["fruits"]);
+
+        let roots = get_root_category_ids(&db).await.unwrap();
+        assert_eq!(roots["fruits"], "food");
+        assert_eq!(roots["snacks"], "snacks");
+    }
+
+    async fn category_ids(db: &DatabaseConnection, sort: CategorySort) -> Vec<String> {
+        get_categories(db, Some(CategoryType::Income), false, sort)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| c.category_id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn reorder_and_usage_sort() {
+        let db = test_connection().await;
+        assert_eq!(
+            category_ids(&db, CategorySort::Manual).await,
+            [
+                "salary",
+                "bonus",
+                "investment",
+                "part_time",
+                "gift",
+                "other"
+            ]
+        );
+
+        assert_eq!(
+            invalid_fields(reorder_categories(&db, vec!["other".into(), "other".into()]).await),
+            ["category_ids"]
+        );
+        assert_eq!(
+            invalid_fields(reorder_categories(&db, vec!["missing".into()]).await),
+            ["category_ids"]
+        );
+
+        // 未列出的分类保持原有相对顺序排在后面
+        reorder_categories(&db, vec!["other".into(), "bonus".into()])
+            .await
+            .unwrap();
+        assert_eq!(
+            category_ids(&db, CategorySort::Manual).await,
+            [
+                "other",
+                "bonus",
+                "salary",
+                "investment",
+                "part_time",
+                "gift"
+            ]
+        );
+
+        for category_id in ["gift", "gift", "investment"] {
+            record_service::create_record(
+                &db,
+                record_data(
+                    crate::entities::RecordType::Income,
+                    category_id,
+                    rust_decimal::Decimal::new(10, 0),
+                    None,
+                ),
+            )
+            .await
+            .unwrap();
+        }
+        assert_eq!(
+            category_ids(&db, CategorySort::Usage).await,
+            [
+                "gift",
+                "investment",
+                "other",
+                "bonus",
+                "salary",
+                "part_time"
+            ]
+        );
+    }
+
+    async fn stored_version(db: &DatabaseConnection) -> Option<String> {
+        settings_service::get_setting(db, settings_service::DEFAULT_CATEGORIES_VERSION_KEY)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn seeding_writes_defaults_once() {
+        let db = test_connection().await;
+        let categories = get_categories(&db, None, true, CategorySort::Manual)
+            .await
+            .unwrap();
+        assert_eq!(categories.len(), DEFAULT_CATEGORIES.len());
+        assert!(categories
+            .iter()
+            .all(|c| c.name_key.as_deref() == Some(c.category_id.as_str())));
+        assert_eq!(stored_version(&db).await.as_deref(), Some("1"));
+
+        // 已记录当前版本时不恢复用户删除的默认分类
+        delete_category(&db, "lottery", CategoryDeleteMode::Block)
+            .await
+            .unwrap();
+        initialize_default_categories(&db).await.unwrap();
+        assert!(get_category_by_id(&db, "lottery").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn seeding_without_version_completes_a_half_seeded_table() {
+        let db = test_connection().await;
+        // 模拟写入默认分类中途失败的旧数据库：只保留前一半，且没有版本号
+        let half = DEFAULT_CATEGORIES.len() / 2;
+        for default in DEFAULT_CATEGORIES[half..].iter().rev() {
+            Category::delete_many()
+                .filter(category::Column::CategoryId.eq(default.category_id))
+                .exec(&db)
+                .await
+                .unwrap();
+        }
+        AppSetting::delete_by_id(settings_service::DEFAULT_CATEGORIES_VERSION_KEY)
+            .exec(&db)
+            .await
+            .unwrap();
+        let food = get_category_by_id(&db, "food").await.unwrap().unwrap();
+        update_category(
+            &db,
+            "food",
+            CategoryData {
+                name: "吃饭".into(),
+                ..category_data("food", "", food.category_type)
+            },
+        )
+        .await
+        .unwrap();
+
+        initialize_default_categories(&db).await.unwrap();
+        for default in DEFAULT_CATEGORIES {
+            let category = get_category_by_id(&db, default.category_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(category.parent_id.as_deref(), default.parent_id);
+        }
+        let food = get_category_by_id(&db, "food").await.unwrap().unwrap();
+        assert_eq!(food.name, "吃饭");
+        assert_eq!(stored_version(&db).await.as_deref(), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn seeding_adds_defaults_newer_than_stored_version() {
+        let db = test_connection().await;
+        delete_category(&db, "lottery", CategoryDeleteMode::Block)
+            .await
+            .unwrap();
+        let food = get_category_by_id(&db, "food").await.unwrap().unwrap();
+        update_category(
+            &db,
+            "food",
+            CategoryData {
+                name: "吃饭".into(),
+                ..category_data("food", "", food.category_type)
+            },
+        )
+        .await
+        .unwrap();
+        settings_service::set_setting(&db, settings_service::DEFAULT_CATEGORIES_VERSION_KEY, "0")
+            .await
+            .unwrap();
+
+        initialize_default_categories(&db).await.unwrap();
+        let lottery = get_category_by_id(&db, "lottery").await.unwrap().unwrap();
+        assert_eq!(lottery.name, "彩票");
+        let food = get_category_by_id(&db, "food").await.unwrap().unwrap();
+        assert_eq!(food.name, "吃饭");
+        assert_eq!(food.name_key, None);
+        assert_eq!(stored_version(&db).await.as_deref(), Some("1"));
+    }
 }
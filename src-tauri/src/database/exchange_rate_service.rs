@@ -0,0 +1,262 @@
+use rust_decimal::Decimal;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::database::validation::{self, Validator};
+use crate::entities::{exchange_rate, ExchangeRate};
+use crate::error::AppError;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExchangeRateData {
+    pub currency: String,
+    pub quote_currency: String,
+    pub rate_date: chrono::NaiveDate,
+    /// 1 单位 currency 可兑换的 quote_currency 数量
+    pub rate: Decimal,
+}
+
+// 按币种过滤，日期从新到旧
+pub async fn get_exchange_rates(
+    db: &DatabaseConnection,
+    currency: Option<String>,
+) -> Result<Vec<exchange_rate::Model>, AppError> {
+    let mut query = ExchangeRate::find();
+
+    if let Some(currency) = currency {
+        query = query.filter(
+            exchange_rate::Column::Currency
+                .eq(currency.clone())
+                .or(exchange_rate::Column::QuoteCurrency.eq(currency)),
+        );
+    }
+
+    Ok(query
+        .order_by_desc(exchange_rate::Column::RateDate)
+        .order_by_asc(exchange_rate::Column::Currency)
+        .all(db)
+        .await?)
+}
+
+fn check_rate_data(validator: &mut Validator, rate_data: &ExchangeRateData) {
+    validation::check_currency(validator, "currency", &rate_data.currency);
+    validation::check_currency(validator, "quote_currency", &rate_data.quote_currency);
+    validator.check(
+        rate_data.currency != rate_data.quote_currency,
+        "quote_currency",
+        "Quote currency must differ from currency",
+    );
+    validator.check(
+        rate_data.rate > Decimal::ZERO,
+        "rate",
+        "Rate must be greater than 0",
+    );
+}
+
+// 同一天同一币种对已有汇率时覆盖
+async fn upsert_rate<C: ConnectionTrait>(
+    db: &C,
+    rate_data: ExchangeRateData,
+) -> Result<(), AppError> {
+    let now = chrono::Utc::now();
+    let rate = exchange_rate::ActiveModel {
+        currency: Set(rate_data.currency),
+        quote_currency: Set(rate_data.quote_currency),
+        rate_date: Set(rate_data.rate_date),
+        rate: Set(rate_data.rate),
+        created_at: Set(now.into()),
+        updated_at: Set(now.into()),
+        ..Default::default()
+    };
+
+    ExchangeRate::insert(rate)
+        .on_conflict(
+            OnConflict::columns([
+                exchange_rate::Column::Currency,
+                exchange_rate::Column::QuoteCurrency,
+                exchange_rate::Column::RateDate,
+            ])
+            .update_columns([
+                exchange_rate::Column::Rate,
+                exchange_rate::Column::UpdatedAt,
+            ])
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn set_exchange_rate(
+    db: &DatabaseConnection,
+    rate_data: ExchangeRateData,
+) -> Result<exchange_rate::Model, AppError> {
+    let mut validator = Validator::new();
+    check_rate_data(&mut validator, &rate_data);
+    validator.finish()?;
+
+    let (currency, quote_currency, rate_date) = (
+        rate_data.currency.clone(),
+        rate_data.quote_currency.clone(),
+        rate_data.rate_date,
+    );
+    upsert_rate(db, rate_data).await?;
+
+    ExchangeRate::find()
+        .filter(exchange_rate::Column::Currency.eq(currency))
+        .filter(exchange_rate::Column::QuoteCurrency.eq(quote_currency))
+        .filter(exchange_rate::Column::RateDate.eq(rate_date))
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::Database("Exchange rate was not saved".to_string()))
+}
+
+pub async fn delete_exchange_rate(db: &DatabaseConnection, id: i32) -> Result<(), AppError> {
+    let result = ExchangeRate::delete_by_id(id).exec(db).await?;
+    if result.rows_affected == 0 {
+        return Err(AppError::not_found("ExchangeRate", id));
+    }
+
+    Ok(())
+}
+
+/// 从 CSV 文本导入汇率，每行 `日期,币种,报价币种,汇率`，如 `2026-10-01,JPY,CNY,0.0478`
+/// 首行为表头时跳过；任一行有误则整体不导入，返回导入的条数
+pub async fn import_exchange_rates(
+    db: &DatabaseConnection,
+    content: &str,
+) -> Result<u64, AppError> {
+    let mut validator = Validator::new();
+    let mut rates = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (index == 0 && line.to_ascii_lowercase().starts_with("date")) {
+            continue;
+        }
+
+        let field = format!("line {}", index + 1);
+        let parts: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [date, currency, quote_currency, rate] = parts[..] else {
+            validator.add(&field, "Expected date,currency,quote_currency,rate");
+            continue;
+        };
+
+        let rate_date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d");
+        let rate = rate.parse::<Decimal>();
+        let (Ok(rate_date), Ok(rate)) = (rate_date, rate) else {
+            validator.add(&field, "Invalid date or rate");
+            continue;
+        };
+
+        let rate_data = ExchangeRateData {
+            currency: currency.to_ascii_uppercase(),
+            quote_currency: quote_currency.to_ascii_uppercase(),
+            rate_date,
+            rate,
+        };
+
+        let mut line_validator = Validator::new();
+        check_rate_data(&mut line_validator, &rate_data);
+        if let Err(AppError::Validation(errors)) = line_validator.finish() {
+            for error in errors {
+                validator.add(&field, format!("{}: {}", error.field, error.message));
+            }
+            continue;
+        }
+
+        rates.push(rate_data);
+    }
+    validator.finish()?;
+
+    let count = rates.len() as u64;
+    let txn = db.begin().await?;
+    for rate_data in rates {
+        upsert_rate(&txn, rate_data).await?;
+    }
+    txn.commit().await?;
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_connection;
+    use crate::database::test_util::date;
+
+    fn rate_data(currency: &str, rate: Decimal) -> ExchangeRateData {
+        ExchangeRateData {
+            currency: currency.into(),
+            quote_currency: "CNY".into(),
+            rate_date: date("2026-10-01"),
+            rate,
+        }
+    }
+
+    #[tokio::test]
+    async fn setting_a_rate_twice_on_one_day_overwrites_it() {
+        let db = test_connection().await;
+        let first = set_exchange_rate(&db, rate_data("USD", Decimal::new(71, 1)))
+            .await
+            .unwrap();
+        let second = set_exchange_rate(&db, rate_data("USD", Decimal::new(72, 1)))
+            .await
+            .unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(second.rate, Decimal::new(72, 1));
+
+        let result = set_exchange_rate(
+            &db,
+            ExchangeRateData {
+                quote_currency: "USD".into(),
+                ..rate_data("USD", Decimal::ZERO)
+            },
+        )
+        .await;
+        match result {
+            Err(AppError::Validation(fields)) => {
+                let fields: Vec<_> = fields.into_iter().map(|field| field.field).collect();
+                assert_eq!(fields, ["quote_currency", "rate"]);
+            }
+            other => panic!("expected validation error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn import_rejects_every_line_when_one_is_invalid() {
+        let db = test_connection().await;
+        let result = import_exchange_rates(
+            &db,
+            "date,currency,quote_currency,rate\n2026-10-01,usd,cny,7.1\n2026-10-02,JPY,CNY\n2026-13-01,JPY,CNY,0.05\n",
+        )
+        .await;
+        match result {
+            Err(AppError::Validation(fields)) => {
+                let fields: Vec<_> = fields.into_iter().map(|field| field.field).collect();
+                assert_eq!(fields, ["line 3", "line 4"]);
+            }
+            other => panic!("expected validation error, got {:?}", other),
+        }
+        assert!(get_exchange_rates(&db, None).await.unwrap().is_empty());
+
+        let count = import_exchange_rates(
+            &db,
+            "date,currency,quote_currency,rate\n2026-10-01,usd,cny,7.1\n\n2026-10-02,JPY,CNY,0.0478\n",
+        )
+        .await
+        .unwrap();
+        assert_eq!(count, 2);
+        let rates: Vec<_> = get_exchange_rates(&db, Some("USD".into()))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|rate| (rate.currency, rate.rate_date))
+            .collect();
+        assert_eq!(rates, [("USD".to_string(), date("2026-10-01"))]);
+    }
+}
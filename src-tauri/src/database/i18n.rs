@@ -0,0 +1,256 @@
+use chrono::Datelike;
+
+use crate::database::budget_service::BudgetAlertNotice;
+use crate::database::category_service::CategoryNode;
+use crate::database::record_service::CategoryBreakdown;
+use crate::entities::{category, BudgetPeriod};
+
+/// 支持的界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    ZhCn,
+    ZhTw,
+    En,
+}
+
+impl Locale {
+    // 按 BCP 47 标签匹配，zh-HK、zh-Hant 等归入繁体，en-US 等归入英文，无法识别时返回 None
+    pub fn parse(tag: &str) -> Option<Locale> {
+        let tag = tag.to_ascii_lowercase().replace('_', "-");
+        let mut parts = tag.split('-');
+
+        match parts.next()? {
+            "en" => Some(Locale::En),
+            "zh" => {
+                if parts.any(|p| matches!(p, "tw" | "hk" | "mo" | "hant")) {
+                    Some(Locale::ZhTw)
+                } else {
+                    Some(Locale::ZhCn)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+// 默认分类名称的翻译：(name_key, 繁体中文, 英文)，简体中文即数据库中保存的名称
+// 全文索引的触发器使用迁移中保存的副本，修改翻译后需要新增迁移重建触发器
+const CATEGORY_NAMES: &[(&str, &str, &str)] = &[
+    ("food", "餐飲", "Food"),
+    ("shopping", "購物", "Shopping"),
+    ("daily", "日用", "Daily Necessities"),
+    ("transport", "交通", "Transport"),
+    ("vegetables", "蔬菜", "Vegetables"),
+    ("fruits", "水果", "Fruits"),
+    ("snacks", "零食", "Snacks"),
+    ("sports", "運動", "Sports"),
+    ("entertainment", "娛樂", "Entertainment"),
+    ("communication", "通訊", "Communication"),
+    ("clothing", "服飾", "Clothing"),
+    ("beauty", "美容", "Beauty"),
+    ("housing", "住房", "Housing"),
+    ("household", "居家", "Household"),
+    ("children", "孩子", "Children"),
+    ("elderly", "長輩", "Elders"),
+    ("social", "社交", "Social"),
+    ("travel", "旅行", "Travel"),
+    ("tobacco", "菸酒", "Tobacco & Alcohol"),
+    ("digital", "數位", "Electronics"),
+    ("car", "汽車", "Car"),
+    ("medical", "醫療", "Medical"),
+    ("books", "書籍", "Books"),
+    ("study", "學習", "Education"),
+    ("pets", "寵物", "Pets"),
+    ("gift_money", "禮金", "Gift Money"),
+    ("gifts", "禮物", "Gifts"),
+    ("office", "辦公", "Office"),
+    ("repair", "維修", "Repairs"),
+    ("donation", "捐贈", "Donations"),
+    ("lottery", "彩券", "Lottery"),
+    ("friends", "親友", "Family & Friends"),
+    ("express", "快遞", "Delivery"),
+    ("settings", "設定", "Settings"),
+    ("salary", "薪資", "Salary"),
+    ("bonus", "獎金", "Bonus"),
+    ("investment", "投資", "Investment"),
+    ("part_time", "兼職", "Part-time"),
+    ("gift", "禮金", "Gift Money"),
+    ("other", "其他", "Other"),
+];
+
+/// 默认分类在指定语言下的名称，没有翻译时返回 None
+pub fn category_name(name_key: &str, locale: Locale) -> Option<&'static str> {
+    let (_, zh_tw, en) = CATEGORY_NAMES.iter().find(|(key, _, _)| *key == name_key)?;
+
+    match locale {
+        Locale::ZhCn => None,
+        Locale::ZhTw => Some(zh_tw),
+        Locale::En => Some(en),
+    }
+}
+
+/// 把带翻译 key 的分类名称替换为指定语言，用户自定义的名称保持不变
+pub fn localize_category(category: &mut category::Model, locale: Locale) {
+    if let Some(name) = category
+        .name_key
+        .as_deref()
+        .and_then(|key| category_name(key, locale))
+    {
+        category.name = name.to_string();
+    }
+}
+
+pub fn localize_category_tree(nodes: &mut [CategoryNode], locale: Locale) {
+    for node in nodes {
+        localize_category(&mut node.category, locale);
+        localize_category_tree(&mut node.children, locale);
+    }
+}
+
+/// 分类汇总中的名称按与 `localize_category` 相同的规则替换
+pub fn localize_category_breakdown(rows: &mut [CategoryBreakdown], locale: Locale) {
+    for row in rows {
+        if let Some(name) = row
+            .name_key
+            .as_deref()
+            .and_then(|key| category_name(key, locale))
+        {
+            row.name = name.to_string();
+        }
+    }
+}
+
+/// 预算提醒系统通知的标题和正文，周期按提醒所属周期的起始日期写明
+pub fn budget_alert_message(notice: &BudgetAlertNotice, locale: Locale) -> (String, String) {
+    let percent = notice.alert.percent_used;
+    let (spent, available) = (notice.spent, notice.available);
+    let start = notice.alert.start_date;
+
+    match locale {
+        Locale::ZhCn | Locale::ZhTw => {
+            let zh_tw = locale == Locale::ZhTw;
+            let period = match notice.period {
+                BudgetPeriod::Weekly => format!(
+                    "{}年{}月{}日{}",
+                    start.year(),
+                    start.month(),
+                    start.day(),
+                    if zh_tw { "當週" } else { "当周" }
+                ),
+                BudgetPeriod::Monthly => format!("{}年{}月", start.year(), start.month()),
+                BudgetPeriod::Yearly => format!("{}年", start.year()),
+            };
+            let name = notice
+                .category_name
+                .as_deref()
+                .unwrap_or(if zh_tw { "總" } else { "总" });
+            let (title, budget) = if zh_tw {
+                ("預算提醒", "預算")
+            } else {
+                ("预算提醒", "预算")
+            };
+            (
+                title.to_string(),
+                format!(
+                    "{}{}{}已使用 {}%（{} / {}）",
+                    period, name, budget, percent, spent, available
+                ),
+            )
+        }
+        Locale::En => {
+            let period = match notice.period {
+                BudgetPeriod::Weekly => format!("the week of {}", start.format("%B %-d, %Y")),
+                BudgetPeriod::Monthly => start.format("%B %Y").to_string(),
+                BudgetPeriod::Yearly => start.year().to_string(),
+            };
+            let name = notice.category_name.as_deref().unwrap_or("Total");
+            (
+                "Budget alert".to_string(),
+                format!(
+                    "{} budget for {} is {}% used ({} / {})",
+                    name, period, percent, spent, available
+                ),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::budget_alert;
+    use rust_decimal::Decimal;
+
+    fn notice(
+        period: BudgetPeriod,
+        start_date: &str,
+        category_name: Option<&str>,
+    ) -> BudgetAlertNotice {
+        BudgetAlertNotice {
+            alert: budget_alert::Model {
+                id: 1,
+                budget_id: 1,
+                start_date: chrono::NaiveDate::parse_from_str(start_date, "%Y-%m-%d").unwrap(),
+                threshold: 80,
+                percent_used: Decimal::new(85, 0),
+                created_at: chrono::Utc::now().into(),
+            },
+            period,
+            category_id: None,
+            category_name: category_name.map(String::from),
+            spent: Decimal::new(170, 0),
+            available: Decimal::new(200, 0),
+        }
+    }
+
+    #[test]
+    fn locale_tags_map_to_supported_locales() {
+        assert_eq!(Locale::parse("zh-CN"), Some(Locale::ZhCn));
+        assert_eq!(Locale::parse("zh_Hans_SG"), Some(Locale::ZhCn));
+        assert_eq!(Locale::parse("zh-HK"), Some(Locale::ZhTw));
+        assert_eq!(Locale::parse("zh-Hant"), Some(Locale::ZhTw));
+        assert_eq!(Locale::parse("en-US"), Some(Locale::En));
+        assert_eq!(Locale::parse("ja-JP"), None);
+    }
+
+    #[test]
+    fn default_category_names_follow_locale() {
+        assert_eq!(category_name("food", Locale::ZhCn), None);
+        assert_eq!(category_name("food", Locale::ZhTw), Some("餐飲"));
+        assert_eq!(category_name("food", Locale::En), Some("Food"));
+        assert_eq!(category_name("missing", Locale::En), None);
+    }
+
+    #[test]
+    fn alert_messages_name_the_period_of_the_alert() {
+        let monthly = notice(BudgetPeriod::Monthly, "2026-09-01", Some("餐饮"));
+        assert_eq!(
+            budget_alert_message(&monthly, Locale::ZhCn),
+            (
+                "预算提醒".to_string(),
+                "2026年9月餐饮预算已使用 85%（170 / 200）".to_string()
+            )
+        );
+
+        let weekly = notice(BudgetPeriod::Weekly, "2026-09-14", None);
+        assert_eq!(
+            budget_alert_message(&weekly, Locale::ZhTw).1,
+            "2026年9月14日當週總預算已使用 85%（170 / 200）"
+        );
+        assert_eq!(
+            budget_alert_message(&weekly, Locale::En).1,
+            "Total budget for the week of September 14, 2026 is 85% used (170 / 200)"
+        );
+
+        let monthly = notice(BudgetPeriod::Monthly, "2026-09-01", Some("Food"));
+        assert_eq!(
+            budget_alert_message(&monthly, Locale::En).1,
+            "Food budget for September 2026 is 85% used (170 / 200)"
+        );
+        let yearly = notice(BudgetPeriod::Yearly, "2026-01-01", None);
+        assert_eq!(
+            budget_alert_message(&yearly, Locale::ZhCn).1,
+            "2026年总预算已使用 85%（170 / 200）"
+        );
+    }
+}
@@ -1,11 +1,76 @@
 use sea_orm::{Database, DatabaseConnection, DbErr};
 use sea_orm_migration::prelude::*;
 use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::watch;
 
+use crate::error::AppError;
 use crate::migration::Migrator;
 
+pub mod account_service;
+pub mod budget_service;
 pub mod category_service;
+pub mod exchange_rate_service;
+pub mod i18n;
 pub mod record_service;
+pub mod recurring_service;
+pub mod settings_service;
+pub mod tag_service;
+pub mod validation;
+
+// 命令等待数据库初始化完成的最长时间
+const DB_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone)]
+enum DbStatus {
+    Pending,
+    Ready(DatabaseConnection),
+    Failed(String),
+}
+
+/// 应用启动时即注册的数据库句柄，连接在后台初始化完成后才可用
+pub struct DbHandle {
+    status: watch::Sender<DbStatus>,
+}
+
+impl DbHandle {
+    pub fn new() -> Self {
+        let (status, _) = watch::channel(DbStatus::Pending);
+        Self { status }
+    }
+
+    pub fn set_ready(&self, db: DatabaseConnection) {
+        self.status.send_replace(DbStatus::Ready(db));
+    }
+
+    pub fn set_failed(&self, message: String) {
+        self.status.send_replace(DbStatus::Failed(message));
+    }
+
+    /// 获取数据库连接，尚未就绪时最多等待 `DB_READY_TIMEOUT`
+    pub async fn connection(&self) -> Result<DatabaseConnection, AppError> {
+        let mut receiver = self.status.subscribe();
+        let status = tokio::time::timeout(
+            DB_READY_TIMEOUT,
+            receiver.wait_for(|status| !matches!(status, DbStatus::Pending)),
+        )
+        .await
+        .map_err(|_| AppError::DatabaseNotReady("Timed out waiting for database".into()))?
+        .map_err(|_| AppError::DatabaseNotReady("Database handle was dropped".into()))?;
+
+        match &*status {
+            DbStatus::Ready(db) => Ok(db.clone()),
+            DbStatus::Failed(message) => Err(AppError::DatabaseNotReady(message.clone())),
+            DbStatus::Pending => unreachable!(),
+        }
+    }
+}
+
+impl Default for DbHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 pub async fn establish_connection() -> Result<DatabaseConnection, DbErr> {
     // 获取应用数据目录
@@ -52,8 +117,9 @@ fn get_app_data_dir() -> PathBuf {
     #[cfg(not(any(target_os = "windows", target_os = "macos")))]
     {
         let mut path = std::env::var("XDG_DATA_HOME")
+            .ok()
             .map(PathBuf::from)
-            .or_else(|_| {
+            .or_else(|| {
                 dirs::home_dir().map(|mut home| {
                     home.push(".local");
                     home.push("share");
@@ -65,3 +131,46 @@ fn get_app_data_dir() -> PathBuf {
         path
     }
 }
+
+// 测试用的内存数据库：执行全部迁移并写入默认分类
+#[cfg(test)]
+pub(crate) async fn test_connection() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+    category_service::initialize_default_categories(&db)
+        .await
+        .unwrap();
+    db
+}
+
+#[cfg(test)]
+pub(crate) mod test_util;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn connection_waits_until_ready() {
+        let handle = Arc::new(DbHandle::new());
+        let waiting = tokio::spawn({
+            let handle = handle.clone();
+            async move { handle.connection().await.is_ok() }
+        });
+
+        handle.set_ready(Database::connect("sqlite::memory:").await.unwrap());
+        assert!(waiting.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn connection_reports_failure() {
+        let handle = DbHandle::new();
+        handle.set_failed("disk full".into());
+
+        match handle.connection().await {
+            Err(AppError::DatabaseNotReady(message)) => assert_eq!(message, "disk full"),
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -1,139 +1,2458 @@
 use rust_decimal::Decimal;
+use sea_orm::entity::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::{Alias, Expr, Func, Query, SelectStatement, SimpleExpr};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
-    Set,
+    ActiveEnum, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection,
+    EntityTrait, FromQueryResult, QueryFilter, QueryOrder, Select, Set, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-use crate::entities::{expense_record, ExpenseRecord};
+use crate::database::validation::{self, Validator};
+use crate::database::{account_service, category_service, settings_service, tag_service};
+use crate::entities::{category, expense_record, record_tag, tag, ExpenseRecord, RecordType};
+use crate::error::AppError;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExpenseRecordData {
-    pub record_type: String,
+    pub record_type: RecordType,
     pub category_id: String,
+    /// 所属账户，可不填
+    pub account_id: Option<i32>,
     pub amount: Decimal,
+    /// 币种，缺省时取账户币种；没有账户时新建取本位币，修改保持原币种
+    pub currency: Option<String>,
     pub note: Option<String>,
+    /// 实际发生时间，缺省为当前时间
+    pub occurred_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    /// 标签名称，不存在的标签自动创建；修改时整体替换原有标签，缺省保持不变
+    pub tags: Option<Vec<String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct RecordFilter {
-    pub record_type: Option<String>,
+    pub record_type: Option<RecordType>,
     pub category_id: Option<String>,
+    pub account_id: Option<i32>,
     pub start_date: Option<chrono::DateTime<chrono::Utc>>,
     pub end_date: Option<chrono::DateTime<chrono::Utc>>,
+    /// 带有其中任一标签
+    pub tags_any: Option<Vec<String>>,
+    /// 带有其中全部标签
+    pub tags_all: Option<Vec<String>>,
+    /// 每页条数，不传则返回全部
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+    /// 从该位置之后继续取，优先于 offset
+    pub cursor: Option<RecordCursor>,
+}
+
+/// 账户间转账
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransferData {
+    pub from_account_id: i32,
+    pub to_account_id: i32,
+    pub amount: Decimal,
+    /// 手续费，从转出账户额外扣除
+    pub fee: Option<Decimal>,
+    pub note: Option<String>,
+    /// 实际发生时间，缺省为当前时间
+    pub occurred_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+}
+
+/// 翻页游标，对应上一页最后一条记录的排序键
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordCursor {
+    pub occurred_at: chrono::DateTime<chrono::Utc>,
+    pub id: i32,
+}
+
+/// 记录及其标签
+#[derive(Debug, Serialize)]
+pub struct TaggedRecord {
+    #[serde(flatten)]
+    pub record: expense_record::Model,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecordPage {
+    pub records: Vec<TaggedRecord>,
+    /// 满足过滤条件的总条数（不受分页影响）
+    pub total: u64,
+    pub has_more: bool,
+    pub next_cursor: Option<RecordCursor>,
+}
+
+// 回收站保留天数，超期的记录在启动时彻底删除
+pub const TRASH_RETENTION_DAYS: i64 = 30;
+
+// 未移入回收站的记录
+fn active_records() -> Select<ExpenseRecord> {
+    ExpenseRecord::find().filter(expense_record::Column::DeletedAt.is_null())
+}
+
+// 按过滤条件（不含分页）筛选未移入回收站的记录
+fn filtered_records(filter: &RecordFilter) -> Select<ExpenseRecord> {
+    let mut query = active_records();
+
+    if let Some(record_type) = filter.record_type {
+        query = query.filter(expense_record::Column::RecordType.eq(record_type));
+    }
+
+    if let Some(category_id) = &filter.category_id {
+        query = query.filter(expense_record::Column::CategoryId.eq(category_id.as_str()));
+    }
+
+    // 转账的转出、转入两侧都算该账户的记录
+    if let Some(account_id) = filter.account_id {
+        query = query.filter(
+            Condition::any()
+                .add(expense_record::Column::AccountId.eq(account_id))
+                .add(expense_record::Column::ToAccountId.eq(account_id)),
+        );
+    }
+
+    if let Some(start_date) = filter.start_date {
+        query = query.filter(expense_record::Column::OccurredAt.gte(start_date));
+    }
+
+    if let Some(end_date) = filter.end_date {
+        query = query.filter(expense_record::Column::OccurredAt.lte(end_date));
+    }
+
+    if let Some(tags) = &filter.tags_any {
+        let tags = tag_service::normalize_tag_names(tags);
+        if !tags.is_empty() {
+            query = query.filter(expense_record::Column::Id.in_subquery(tagged_record_ids(tags)));
+        }
+    }
+
+    if let Some(tags) = &filter.tags_all {
+        let tags = tag_service::normalize_tag_names(tags);
+        if !tags.is_empty() {
+            let count = tags.len() as i64;
+            let subquery = tagged_record_ids(tags)
+                .group_by_col((record_tag::Entity, record_tag::Column::RecordId))
+                .and_having(
+                    Expr::expr(Func::count_distinct(Expr::col((
+                        record_tag::Entity,
+                        record_tag::Column::TagId,
+                    ))))
+                    .eq(count),
+                )
+                .to_owned();
+            query = query.filter(expense_record::Column::Id.in_subquery(subquery));
+        }
+    }
+
+    query
 }
 
 pub async fn get_records(
     db: &DatabaseConnection,
     filter: Option<RecordFilter>,
-) -> Result<Vec<expense_record::Model>, DbErr> {
-    let mut query = ExpenseRecord::find().order_by_desc(expense_record::Column::CreatedAt);
+) -> Result<RecordPage, AppError> {
+    use sea_orm::{PaginatorTrait, QuerySelect};
 
-    if let Some(filter) = filter {
-        if let Some(record_type) = filter.record_type {
-            query = query.filter(expense_record::Column::RecordType.eq(record_type));
-        }
+    let filter = filter.unwrap_or_default();
 
-        if let Some(category_id) = filter.category_id {
-            query = query.filter(expense_record::Column::CategoryId.eq(category_id));
-        }
+    let mut query = filtered_records(&filter);
 
-        if let Some(start_date) = filter.start_date {
-            query = query.filter(expense_record::Column::CreatedAt.gte(start_date));
-        }
+    let total = query.clone().count(db).await?;
 
-        if let Some(end_date) = filter.end_date {
-            query = query.filter(expense_record::Column::CreatedAt.lte(end_date));
+    query = query
+        .order_by_desc(expense_record::Column::OccurredAt)
+        .order_by_desc(expense_record::Column::Id);
+
+    if let Some(cursor) = filter.cursor {
+        query = query.filter(
+            Condition::any()
+                .add(expense_record::Column::OccurredAt.lt(cursor.occurred_at))
+                .add(
+                    Condition::all()
+                        .add(expense_record::Column::OccurredAt.eq(cursor.occurred_at))
+                        .add(expense_record::Column::Id.lt(cursor.id)),
+                ),
+        );
+    } else if let Some(offset) = filter.offset {
+        query = query.offset(offset);
+    }
+
+    // 多取一条用于判断是否还有下一页
+    if let Some(limit) = filter.limit {
+        query = query.limit(limit + 1);
+    }
+
+    let mut records = query.all(db).await?;
+
+    let has_more = match filter.limit {
+        Some(limit) if records.len() as u64 > limit => {
+            records.truncate(limit as usize);
+            true
         }
+        _ => false,
+    };
+
+    let next_cursor = if has_more {
+        records.last().map(|record| RecordCursor {
+            occurred_at: record.occurred_at.with_timezone(&chrono::Utc),
+            id: record.id,
+        })
+    } else {
+        None
+    };
+
+    let records = with_tags(db, records).await?;
+
+    Ok(RecordPage {
+        records,
+        total,
+        has_more,
+        next_cursor,
+    })
+}
+
+// 全文检索条件：不少于三个字符的词用 MATCH，trigram 分词下也能匹配词的一部分和前缀；
+// 更短的词（如两个汉字）无法用 trigram 索引，改为在三个索引列上做 LIKE 子串匹配
+// 与 expense_records 连接查询，列名需要带表名
+fn search_condition(terms: &[&str]) -> (String, Vec<String>, bool) {
+    let mut clauses = Vec::new();
+    let mut values = Vec::new();
+
+    let match_terms: Vec<String> = terms
+        .iter()
+        .filter(|term| term.chars().count() >= 3)
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    let has_match = !match_terms.is_empty();
+    if has_match {
+        clauses.push(r#""record_search" MATCH ?"#.to_string());
+        values.push(match_terms.join(" "));
+    }
+
+    // 自定义 SQL 中的反斜杠会被 sea-query 当作转义符，LIKE 改用 ! 转义
+    for term in terms.iter().filter(|term| term.chars().count() < 3) {
+        clauses.push(
+            r#"("record_search"."note" LIKE ? ESCAPE '!'
+                OR "record_search"."category" LIKE ? ESCAPE '!'
+                OR "record_search"."tags" LIKE ? ESCAPE '!')"#
+                .to_string(),
+        );
+        let pattern = format!(
+            "%{}%",
+            term.replace('!', "!!")
+                .replace('%', "!%")
+                .replace('_', "!_")
+        );
+        values.extend(std::iter::repeat_n(pattern, 3));
     }
 
-    query.all(db).await
+    (clauses.join(" AND "), values, has_match)
+}
+
+/// 在备注、分类名称和标签中搜索记录，可与 `RecordFilter` 的其他条件组合
+/// 结果按相关度排列，相关度相同时按发生时间倒序；只支持 offset 分页
+pub async fn search_records(
+    db: &DatabaseConnection,
+    query: &str,
+    filter: Option<RecordFilter>,
+) -> Result<RecordPage, AppError> {
+    use sea_orm::{PaginatorTrait, QuerySelect, QueryTrait};
+
+    let filter = filter.unwrap_or_default();
+
+    let terms: Vec<&str> = query.split_whitespace().collect();
+    let mut validator = Validator::new();
+    validator.check(!terms.is_empty(), "query", "Search query must not be empty");
+    validator.check(
+        query.chars().count() <= validation::MAX_SEARCH_QUERY_LENGTH,
+        "query",
+        format!(
+            "Search query must be at most {} characters",
+            validation::MAX_SEARCH_QUERY_LENGTH
+        ),
+    );
+    validator.check(
+        filter.cursor.is_none(),
+        "cursor",
+        "Search results are paged by offset",
+    );
+    validator.finish()?;
+
+    let (condition, values, has_match) = search_condition(&terms);
+
+    let mut select = filtered_records(&filter);
+    QueryTrait::query(&mut select)
+        .inner_join(
+            Alias::new("record_search"),
+            Expr::col((Alias::new("record_search"), Alias::new("rowid")))
+                .equals((expense_record::Entity, expense_record::Column::Id)),
+        )
+        .and_where(Expr::cust_with_values(condition, values));
+
+    let total = select.clone().count(db).await?;
+
+    // bm25 越小越相关；备注与标签的权重高于分类名称。只有 LIKE 条件时没有相关度
+    if has_match {
+        select = select.order_by_asc(Expr::cust(r#"bm25("record_search", 2.0, 1.0, 2.0)"#));
+    }
+    select = select
+        .order_by_desc(expense_record::Column::OccurredAt)
+        .order_by_desc(expense_record::Column::Id);
+
+    if let Some(offset) = filter.offset {
+        select = select.offset(offset);
+    }
+    // 多取一条用于判断是否还有下一页
+    if let Some(limit) = filter.limit {
+        select = select.limit(limit + 1);
+    }
+
+    let mut records = select.all(db).await?;
+    let has_more = match filter.limit {
+        Some(limit) if records.len() as u64 > limit => {
+            records.truncate(limit as usize);
+            true
+        }
+        _ => false,
+    };
+
+    let records = with_tags(db, records).await?;
+
+    Ok(RecordPage {
+        records,
+        total,
+        has_more,
+        next_cursor: None,
+    })
+}
+
+// 带有任一指定标签的记录 ID
+fn tagged_record_ids(tags: Vec<String>) -> SelectStatement {
+    Query::select()
+        .column((record_tag::Entity, record_tag::Column::RecordId))
+        .from(record_tag::Entity)
+        .inner_join(
+            tag::Entity,
+            Expr::col((tag::Entity, tag::Column::Id))
+                .equals((record_tag::Entity, record_tag::Column::TagId)),
+        )
+        .and_where(Expr::col((tag::Entity, tag::Column::Name)).is_in(tags))
+        .to_owned()
+}
+
+/// 为记录附上标签
+pub async fn with_tags(
+    db: &DatabaseConnection,
+    records: Vec<expense_record::Model>,
+) -> Result<Vec<TaggedRecord>, AppError> {
+    let ids: Vec<i32> = records.iter().map(|record| record.id).collect();
+    let mut tags = tag_service::get_record_tags(db, &ids).await?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| TaggedRecord {
+            tags: tags.remove(&record.id).unwrap_or_default(),
+            record,
+        })
+        .collect())
 }
 
 pub async fn get_record_by_id(
     db: &DatabaseConnection,
     id: i32,
-) -> Result<Option<expense_record::Model>, DbErr> {
-    ExpenseRecord::find_by_id(id).one(db).await
+) -> Result<Option<expense_record::Model>, AppError> {
+    Ok(active_records()
+        .filter(expense_record::Column::Id.eq(id))
+        .one(db)
+        .await?)
+}
+
+fn check_note(validator: &mut Validator, note: &Option<String>) {
+    if let Some(note) = note {
+        validator.check(
+            note.chars().count() <= validation::MAX_NOTE_LENGTH,
+            "note",
+            format!(
+                "Note must be at most {} characters",
+                validation::MAX_NOTE_LENGTH
+            ),
+        );
+    }
+}
+
+// 校验记录数据：类型、金额、备注长度，分类存在且类型一致，以及币种与账户一致
+// 已归档的分类只允许原本就属于该分类的记录继续使用；返回最终使用的币种
+pub(crate) async fn validate_record(
+    db: &DatabaseConnection,
+    record_data: &ExpenseRecordData,
+    current: Option<&expense_record::Model>,
+) -> Result<String, AppError> {
+    if record_data.record_type == RecordType::Transfer {
+        return Err(AppError::validation(
+            "record_type",
+            "Use create_transfer for transfers",
+        ));
+    }
+
+    let mut validator = Validator::new();
+
+    validation::check_amount(&mut validator, "amount", record_data.amount);
+
+    check_note(&mut validator, &record_data.note);
+
+    if let Some(tags) = &record_data.tags {
+        tag_service::check_tags(&mut validator, "tags", tags);
+    }
+
+    match category_service::get_category_by_id(db, &record_data.category_id).await? {
+        Some(category) => {
+            validator.check(
+                category.category_type.accepts(record_data.record_type),
+                "category_id",
+                format!(
+                    "Category cannot be used for {} records",
+                    record_data.record_type.to_value()
+                ),
+            );
+            validator.check(
+                !category.archived
+                    || current.and_then(|r| r.category_id.as_deref())
+                        == Some(category.category_id.as_str()),
+                "category_id",
+                "Category is archived",
+            );
+        }
+        None => validator.add("category_id", "Category does not exist"),
+    }
+
+    let account = match record_data.account_id {
+        Some(account_id) => {
+            let account = account_service::get_account_by_id(db, account_id).await?;
+            validator.check(account.is_some(), "account_id", "Account does not exist");
+            account
+        }
+        None => None,
+    };
+
+    let currency = match (&record_data.currency, &account, current) {
+        (Some(currency), _, _) => currency.clone(),
+        (None, Some(account), _) => account.currency.clone(),
+        (None, None, Some(record)) => record.currency.clone(),
+        (None, None, None) => settings_service::get_base_currency(db).await?,
+    };
+    validation::check_currency(&mut validator, "currency", &currency);
+    if let Some(account) = &account {
+        validator.check(
+            account.currency == currency,
+            "currency",
+            format!("Account uses {}", account.currency),
+        );
+    }
+
+    validator.finish()?;
+    Ok(currency)
 }
 
 pub async fn create_record(
     db: &DatabaseConnection,
     record_data: ExpenseRecordData,
-) -> Result<expense_record::Model, DbErr> {
-    let now = chrono::Utc::now().into();
+) -> Result<TaggedRecord, AppError> {
+    let currency = validate_record(db, &record_data, None).await?;
+
+    let now = chrono::Utc::now();
+    let occurred_at = record_data
+        .occurred_at
+        .map(|t| t.with_timezone(&chrono::Utc))
+        .unwrap_or(now);
     let record = expense_record::ActiveModel {
         record_type: Set(record_data.record_type),
-        category_id: Set(record_data.category_id),
+        category_id: Set(Some(record_data.category_id)),
+        account_id: Set(record_data.account_id),
         amount: Set(record_data.amount),
+        currency: Set(currency),
         note: Set(record_data.note),
-        created_at: Set(now),
-        updated_at: Set(now),
+        occurred_at: Set(occurred_at.into()),
+        created_at: Set(now.into()),
+        updated_at: Set(now.into()),
         ..Default::default()
     };
 
-    record.insert(db).await
+    let tags = record_data.tags.unwrap_or_default();
+
+    let txn = db.begin().await?;
+    let record = record.insert(&txn).await?;
+    tag_service::set_record_tags(&txn, record.id, &tags).await?;
+    txn.commit().await?;
+
+    Ok(TaggedRecord {
+        record,
+        tags: tag_service::normalize_tag_names(&tags),
+    })
 }
 
 pub async fn update_record(
     db: &DatabaseConnection,
     id: i32,
     record_data: ExpenseRecordData,
-) -> Result<expense_record::Model, DbErr> {
-    let record: Option<expense_record::Model> = ExpenseRecord::find_by_id(id).one(db).await?;
+) -> Result<TaggedRecord, AppError> {
+    let record = get_record_by_id(db, id).await?;
 
     if let Some(record) = record {
+        if record.record_type == RecordType::Transfer {
+            return Err(AppError::validation(
+                "record_type",
+                "Use update_transfer for transfers",
+            ));
+        }
+        let currency = validate_record(db, &record_data, Some(&record)).await?;
+
         let mut record: expense_record::ActiveModel = record.into();
         record.record_type = Set(record_data.record_type);
-        record.category_id = Set(record_data.category_id);
+        record.category_id = Set(Some(record_data.category_id));
+        record.account_id = Set(record_data.account_id);
         record.amount = Set(record_data.amount);
+        record.currency = Set(currency);
         record.note = Set(record_data.note);
+        if let Some(occurred_at) = record_data.occurred_at {
+            record.occurred_at = Set(occurred_at.with_timezone(&chrono::Utc).into());
+        }
         record.updated_at = Set(chrono::Utc::now().into());
 
-        record.update(db).await
+        let txn = db.begin().await?;
+        let record = record.update(&txn).await?;
+        if let Some(tags) = &record_data.tags {
+            tag_service::set_record_tags(&txn, record.id, tags).await?;
+        }
+        txn.commit().await?;
+
+        let mut records = with_tags(db, vec![record]).await?;
+        Ok(records.remove(0))
     } else {
-        Err(DbErr::RecordNotFound("Record not found".into()))
+        Err(AppError::not_found("Record", id))
     }
 }
 
-pub async fn delete_record(db: &DatabaseConnection, id: i32) -> Result<(), DbErr> {
-    ExpenseRecord::delete_by_id(id).exec(db).await?;
+// 校验转账：金额、手续费、备注长度，以及两个账户存在、不同且币种一致；返回转账币种
+async fn validate_transfer(
+    db: &DatabaseConnection,
+    transfer_data: &TransferData,
+) -> Result<String, AppError> {
+    let mut validator = Validator::new();
+
+    validation::check_amount(&mut validator, "amount", transfer_data.amount);
+
+    if let Some(fee) = transfer_data.fee {
+        validator.check(fee >= Decimal::ZERO, "fee", "Fee must not be negative");
+        validator.check(
+            fee.normalize().scale() <= 2,
+            "fee",
+            "Fee must have at most 2 decimal places",
+        );
+    }
+
+    check_note(&mut validator, &transfer_data.note);
+
+    validator.check(
+        transfer_data.from_account_id != transfer_data.to_account_id,
+        "to_account_id",
+        "Cannot transfer to the same account",
+    );
+
+    let from = account_service::get_account_by_id(db, transfer_data.from_account_id).await?;
+    let to = account_service::get_account_by_id(db, transfer_data.to_account_id).await?;
+    validator.check(from.is_some(), "from_account_id", "Account does not exist");
+    validator.check(to.is_some(), "to_account_id", "Account does not exist");
+    if let (Some(from), Some(to)) = (&from, &to) {
+        validator.check(
+            from.currency == to.currency,
+            "to_account_id",
+            "Accounts must use the same currency",
+        );
+    }
+
+    validator.finish()?;
+    Ok(from.map(|a| a.currency).unwrap_or_default())
+}
+
+pub async fn create_transfer(
+    db: &DatabaseConnection,
+    transfer_data: TransferData,
+) -> Result<expense_record::Model, AppError> {
+    let currency = validate_transfer(db, &transfer_data).await?;
+
+    let now = chrono::Utc::now();
+    let occurred_at = transfer_data
+        .occurred_at
+        .map(|t| t.with_timezone(&chrono::Utc))
+        .unwrap_or(now);
+    let record = expense_record::ActiveModel {
+        record_type: Set(RecordType::Transfer),
+        category_id: Set(None),
+        account_id: Set(Some(transfer_data.from_account_id)),
+        to_account_id: Set(Some(transfer_data.to_account_id)),
+        amount: Set(transfer_data.amount),
+        currency: Set(currency),
+        fee: Set(transfer_data.fee),
+        note: Set(transfer_data.note),
+        occurred_at: Set(occurred_at.into()),
+        created_at: Set(now.into()),
+        updated_at: Set(now.into()),
+        ..Default::default()
+    };
+
+    Ok(record.insert(db).await?)
+}
+
+pub async fn update_transfer(
+    db: &DatabaseConnection,
+    id: i32,
+    transfer_data: TransferData,
+) -> Result<expense_record::Model, AppError> {
+    let record = get_record_by_id(db, id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Record", id))?;
+
+    if record.record_type != RecordType::Transfer {
+        return Err(AppError::validation(
+            "record_type",
+            "Record is not a transfer",
+        ));
+    }
+    let currency = validate_transfer(db, &transfer_data).await?;
+
+    let mut record: expense_record::ActiveModel = record.into();
+    record.account_id = Set(Some(transfer_data.from_account_id));
+    record.to_account_id = Set(Some(transfer_data.to_account_id));
+    record.amount = Set(transfer_data.amount);
+    record.currency = Set(currency);
+    record.fee = Set(transfer_data.fee);
+    record.note = Set(transfer_data.note);
+    if let Some(occurred_at) = transfer_data.occurred_at {
+        record.occurred_at = Set(occurred_at.with_timezone(&chrono::Utc).into());
+    }
+    record.updated_at = Set(chrono::Utc::now().into());
+
+    Ok(record.update(db).await?)
+}
+
+// 删除只是移入回收站
+pub async fn delete_record(db: &DatabaseConnection, id: i32) -> Result<(), AppError> {
+    let result = ExpenseRecord::update_many()
+        .col_expr(
+            expense_record::Column::DeletedAt,
+            Expr::value(chrono::Utc::now()),
+        )
+        .filter(expense_record::Column::Id.eq(id))
+        .filter(expense_record::Column::DeletedAt.is_null())
+        .exec(db)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(AppError::not_found("Record", id));
+    }
+
+    Ok(())
+}
+
+// 回收站中的记录，最近删除的在前
+pub async fn get_trash(db: &DatabaseConnection) -> Result<Vec<expense_record::Model>, AppError> {
+    Ok(ExpenseRecord::find()
+        .filter(expense_record::Column::DeletedAt.is_not_null())
+        .order_by_desc(expense_record::Column::DeletedAt)
+        .order_by_desc(expense_record::Column::Id)
+        .all(db)
+        .await?)
+}
+
+pub async fn restore_record(
+    db: &DatabaseConnection,
+    id: i32,
+) -> Result<expense_record::Model, AppError> {
+    let record = ExpenseRecord::find_by_id(id)
+        .filter(expense_record::Column::DeletedAt.is_not_null())
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Record", id))?;
+
+    let mut record: expense_record::ActiveModel = record.into();
+    record.deleted_at = Set(None);
+    record.updated_at = Set(chrono::Utc::now().into());
+
+    Ok(record.update(db).await?)
+}
+
+// 彻底删除，只能作用于回收站中的记录
+pub async fn purge_record(db: &DatabaseConnection, id: i32) -> Result<(), AppError> {
+    let result = ExpenseRecord::delete_many()
+        .filter(expense_record::Column::Id.eq(id))
+        .filter(expense_record::Column::DeletedAt.is_not_null())
+        .exec(db)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(AppError::not_found("Record", id));
+    }
+
     Ok(())
 }
 
+// 清空回收站，返回删除的条数
+pub async fn empty_trash(db: &DatabaseConnection) -> Result<u64, AppError> {
+    let result = ExpenseRecord::delete_many()
+        .filter(expense_record::Column::DeletedAt.is_not_null())
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected)
+}
+
+// 彻底删除在回收站中超过 retention_days 天的记录，返回删除的条数
+pub async fn purge_expired_trash(
+    db: &DatabaseConnection,
+    retention_days: i64,
+) -> Result<u64, AppError> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(retention_days);
+    let result = ExpenseRecord::delete_many()
+        .filter(expense_record::Column::DeletedAt.lt(cutoff))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected)
+}
+
+// 按记录类型和发生时间范围构造过滤条件
+fn range_condition(
+    record_type: Option<RecordType>,
+    start_date: Option<chrono::DateTime<chrono::Utc>>,
+    end_date: Option<chrono::DateTime<chrono::Utc>>,
+) -> Condition {
+    let mut condition = Condition::all();
+
+    // 未指定类型时只统计收支，不含转账
+    match record_type {
+        Some(record_type) => {
+            condition = condition.add(expense_record::Column::RecordType.eq(record_type));
+        }
+        None => {
+            condition = condition.add(expense_record::Column::RecordType.ne(RecordType::Transfer));
+        }
+    }
+
+    if let Some(start_date) = start_date {
+        condition = condition.add(expense_record::Column::OccurredAt.gte(start_date));
+    }
+
+    if let Some(end_date) = end_date {
+        condition = condition.add(expense_record::Column::OccurredAt.lte(end_date));
+    }
+
+    condition
+}
+
+// 只累加指定类型记录金额的 SUM 表达式，金额为记录本身的币种
+fn sum_by_type(record_type: RecordType) -> SimpleExpr {
+    sum_amount_by_type(
+        record_type,
+        Expr::col(expense_record::Column::Amount).into(),
+    )
+}
+
+// 同上，金额按记录日期的汇率换算为本位币
+fn sum_converted_by_type(record_type: RecordType, base_currency: &str) -> SimpleExpr {
+    sum_amount_by_type(record_type, converted_amount(base_currency))
+}
+
+fn sum_amount_by_type(record_type: RecordType, amount: SimpleExpr) -> SimpleExpr {
+    sum_cents(
+        Expr::case(expense_record::Column::RecordType.eq(record_type), amount)
+            .finally(0)
+            .into(),
+    )
+}
+
+// 每条金额先四舍五入到分再按整数求和，避免浮点累加误差；结果按 i64 读取后用 from_cents 换回金额
+fn sum_cents(amount: SimpleExpr) -> SimpleExpr {
+    Func::sum(Func::cast_as(
+        Func::round(amount.mul(100)),
+        Alias::new("INTEGER"),
+    ))
+    .into()
+}
+
+fn from_cents(cents: Option<i64>) -> Decimal {
+    Decimal::new(cents.unwrap_or_default(), 2)
+}
+
+// 记录币种兑本位币的汇率：取记录本地日期当天或之前最近的一条，正向没有时用反向汇率的倒数
+// 与本位币相同时为 1，找不到汇率时为 NULL
+fn exchange_rate_expr(base_currency: &str) -> SimpleExpr {
+    Expr::cust_with_values(
+        r#"(CASE WHEN "expense_records"."currency" = ? THEN 1 ELSE COALESCE(
+            (SELECT "er"."rate" FROM "exchange_rates" AS "er"
+                WHERE "er"."currency" = "expense_records"."currency" AND "er"."quote_currency" = ?
+                AND "er"."rate_date" <= date("expense_records"."occurred_at", 'localtime')
+                ORDER BY "er"."rate_date" DESC LIMIT 1),
+            (SELECT 1.0 / "er"."rate" FROM "exchange_rates" AS "er"
+                WHERE "er"."currency" = ? AND "er"."quote_currency" = "expense_records"."currency"
+                AND "er"."rate_date" <= date("expense_records"."occurred_at", 'localtime')
+                ORDER BY "er"."rate_date" DESC LIMIT 1)
+        ) END)"#,
+        [base_currency; 3],
+    )
+}
+
+fn converted_amount(base_currency: &str) -> SimpleExpr {
+    Expr::col(expense_record::Column::Amount).mul(exchange_rate_expr(base_currency))
+}
+
+// 能找到汇率（或与本位币相同）的记录，统计时只计入这些记录
+fn has_exchange_rate(base_currency: &str) -> SimpleExpr {
+    Expr::expr(exchange_rate_expr(base_currency)).is_not_null()
+}
+
+// 范围内找不到汇率的收支记录的币种，这些记录不计入统计，由界面提示补录汇率
+async fn missing_exchange_rates(
+    db: &DatabaseConnection,
+    condition: Condition,
+    base_currency: &str,
+) -> Result<Vec<String>, AppError> {
+    use sea_orm::QuerySelect;
+
+    Ok(active_records()
+        .select_only()
+        .column(expense_record::Column::Currency)
+        .distinct()
+        .filter(condition)
+        .filter(expense_record::Column::RecordType.ne(RecordType::Transfer))
+        .filter(Expr::expr(exchange_rate_expr(base_currency)).is_null())
+        .order_by_asc(expense_record::Column::Currency)
+        .into_tuple()
+        .all(db)
+        .await?)
+}
+
+/// 指定时间范围内的统计结果，金额均已换算为本位币
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatisticsReport {
+    /// 本位币
+    pub currency: String,
+    pub income_total: Decimal,
+    pub expense_total: Decimal,
+    /// 收入减支出
+    pub net_balance: Decimal,
+    pub record_count: i64,
+    /// 日均支出，天数取查询范围，未指定时取首末两笔记录之间的天数
+    pub average_per_day: Decimal,
+    /// 换算为本位币后金额最大的单笔记录
+    pub max_record: Option<expense_record::Model>,
+    /// 缺少汇率、未计入统计的记录币种
+    pub missing_rates: Vec<String>,
+}
+
 // 获取统计数据
 pub async fn get_statistics(
     db: &DatabaseConnection,
-    record_type: Option<String>,
+    record_type: Option<RecordType>,
     start_date: Option<chrono::DateTime<chrono::Utc>>,
     end_date: Option<chrono::DateTime<chrono::Utc>>,
-) -> Result<Decimal, DbErr> {
-    use sea_orm::{FromQueryResult, QuerySelect};
+) -> Result<StatisticsReport, AppError> {
+    use sea_orm::QuerySelect;
 
     #[derive(Debug, FromQueryResult)]
-    struct SumResult {
-        total: Option<Decimal>,
+    struct SummaryResult {
+        income_total: Option<i64>,
+        expense_total: Option<i64>,
+        record_count: i64,
+        first_at: Option<DateTimeWithTimeZone>,
+        last_at: Option<DateTimeWithTimeZone>,
     }
 
-    let mut query = ExpenseRecord::find()
+    let condition = range_condition(record_type, start_date, end_date);
+    let base_currency = settings_service::get_base_currency(db).await?;
+    let missing_rates = missing_exchange_rates(db, condition.clone(), &base_currency).await?;
+    let condition = condition.add(has_exchange_rate(&base_currency));
+
+    let summary = active_records()
         .select_only()
-        .column_as(expense_record::Column::Amount.sum(), "total");
+        .column_as(
+            sum_converted_by_type(RecordType::Income, &base_currency),
+            "income_total",
+        )
+        .column_as(
+            sum_converted_by_type(RecordType::Expense, &base_currency),
+            "expense_total",
+        )
+        .column_as(expense_record::Column::Id.count(), "record_count")
+        .column_as(expense_record::Column::OccurredAt.min(), "first_at")
+        .column_as(expense_record::Column::OccurredAt.max(), "last_at")
+        .filter(condition.clone())
+        .into_model::<SummaryResult>()
+        .one(db)
+        .await?;
 
-    if let Some(record_type) = record_type {
-        query = query.filter(expense_record::Column::RecordType.eq(record_type));
+    let max_record = active_records()
+        .filter(condition)
+        .order_by_desc(converted_amount(&base_currency))
+        .order_by_desc(expense_record::Column::OccurredAt)
+        .one(db)
+        .await?;
+
+    let (income_total, expense_total, record_count, first_at, last_at) = match summary {
+        Some(s) => (
+            from_cents(s.income_total),
+            from_cents(s.expense_total),
+            s.record_count,
+            s.first_at.map(|t| t.with_timezone(&chrono::Utc)),
+            s.last_at.map(|t| t.with_timezone(&chrono::Utc)),
+        ),
+        None => (Decimal::ZERO, Decimal::ZERO, 0, None, None),
+    };
+
+    // 不足一天按一天计
+    let days = match (start_date.or(first_at), end_date.or(last_at)) {
+        (Some(start), Some(end)) if end > start => {
+            let seconds = (end - start).num_seconds();
+            (seconds + 86_399) / 86_400
+        }
+        _ => 1,
+    };
+
+    Ok(StatisticsReport {
+        currency: base_currency,
+        income_total,
+        expense_total,
+        net_balance: income_total - expense_total,
+        record_count,
+        average_per_day: (expense_total / Decimal::from(days)).round_dp(2),
+        max_record,
+        missing_rates,
+    })
+}
+
+/// 单个账户的资金流入流出合计
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AccountFlows {
+    pub income_total: Decimal,
+    pub expense_total: Decimal,
+    pub transfer_in_total: Decimal,
+    /// 转出金额加手续费
+    pub transfer_out_total: Decimal,
+}
+
+// 各账户的资金流入流出，不含回收站中的记录
+pub async fn sum_by_account(
+    db: &DatabaseConnection,
+) -> Result<HashMap<i32, AccountFlows>, AppError> {
+    use sea_orm::QuerySelect;
+
+    #[derive(Debug, FromQueryResult)]
+    struct OutgoingTotals {
+        account_id: i32,
+        income_total: Option<i64>,
+        expense_total: Option<i64>,
+        transfer_total: Option<i64>,
+        fee_total: Option<i64>,
     }
 
-    if let Some(start_date) = start_date {
-        query = query.filter(expense_record::Column::CreatedAt.gte(start_date));
+    #[derive(Debug, FromQueryResult)]
+    struct IncomingTotals {
+        to_account_id: i32,
+        transfer_total: Option<i64>,
     }
 
-    if let Some(end_date) = end_date {
-        query = query.filter(expense_record::Column::CreatedAt.lte(end_date));
+    let outgoing = active_records()
+        .select_only()
+        .column(expense_record::Column::AccountId)
+        .column_as(sum_by_type(RecordType::Income), "income_total")
+        .column_as(sum_by_type(RecordType::Expense), "expense_total")
+        .column_as(sum_by_type(RecordType::Transfer), "transfer_total")
+        .column_as(
+            sum_cents(Expr::col(expense_record::Column::Fee).into()),
+            "fee_total",
+        )
+        .filter(expense_record::Column::AccountId.is_not_null())
+        .group_by(expense_record::Column::AccountId)
+        .into_model::<OutgoingTotals>()
+        .all(db)
+        .await?;
+
+    let incoming = active_records()
+        .select_only()
+        .column(expense_record::Column::ToAccountId)
+        .column_as(sum_by_type(RecordType::Transfer), "transfer_total")
+        .filter(expense_record::Column::ToAccountId.is_not_null())
+        .group_by(expense_record::Column::ToAccountId)
+        .into_model::<IncomingTotals>()
+        .all(db)
+        .await?;
+
+    let mut flows: HashMap<i32, AccountFlows> = HashMap::new();
+    for row in outgoing {
+        let flow = flows.entry(row.account_id).or_default();
+        flow.income_total = from_cents(row.income_total);
+        flow.expense_total = from_cents(row.expense_total);
+        flow.transfer_out_total = from_cents(row.transfer_total) + from_cents(row.fee_total);
     }
+    for row in incoming {
+        flows
+            .entry(row.to_account_id)
+            .or_default()
+            .transfer_in_total = from_cents(row.transfer_total);
+    }
+
+    Ok(flows)
+}
 
-    let result: Option<SumResult> = query.into_model::<SumResult>().one(db).await?;
+/// 各分类自 since 起的记录数（不含回收站），用于按使用频率排序
+pub async fn count_records_by_category(
+    db: &DatabaseConnection,
+    since: chrono::DateTime<chrono::Utc>,
+) -> Result<HashMap<String, i64>, AppError> {
+    use sea_orm::QuerySelect;
+
+    let rows: Vec<(String, i64)> = active_records()
+        .select_only()
+        .column(expense_record::Column::CategoryId)
+        .column_as(expense_record::Column::Id.count(), "count")
+        .filter(range_condition(None, Some(since), None))
+        .group_by(expense_record::Column::CategoryId)
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// [start, end) 区间内各分类的支出合计，已换算为本位币；同时返回缺少汇率、未计入的记录币种
+pub(crate) async fn sum_expense_by_category(
+    db: &DatabaseConnection,
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+) -> Result<(HashMap<String, Decimal>, Vec<String>), AppError> {
+    use sea_orm::QuerySelect;
 
-    Ok(result
-        .and_then(|r| r.total)
-        .unwrap_or_else(|| Decimal::from(0)))
+    #[derive(Debug, FromQueryResult)]
+    struct CategoryTotal {
+        category_id: String,
+        total: Option<i64>,
+    }
+
+    let condition = Condition::all()
+        .add(expense_record::Column::RecordType.eq(RecordType::Expense))
+        .add(expense_record::Column::OccurredAt.gte(start))
+        .add(expense_record::Column::OccurredAt.lt(end));
+    let base_currency = settings_service::get_base_currency(db).await?;
+    let missing_rates = missing_exchange_rates(db, condition.clone(), &base_currency).await?;
+    let condition = condition.add(has_exchange_rate(&base_currency));
+
+    let rows = active_records()
+        .select_only()
+        .column(expense_record::Column::CategoryId)
+        .column_as(
+            sum_converted_by_type(RecordType::Expense, &base_currency),
+            "total",
+        )
+        .filter(condition)
+        .group_by(expense_record::Column::CategoryId)
+        .into_model::<CategoryTotal>()
+        .all(db)
+        .await?;
+
+    let totals = rows
+        .into_iter()
+        .map(|row| (row.category_id, from_cents(row.total)))
+        .collect();
+
+    Ok((totals, missing_rates))
+}
+
+/// [start, end) 区间内按周期和分类汇总的支出，键为 (周期标识, 分类)，周期标识同 `TimeBucket::period`
+/// 缺少汇率的记录不计入
+pub(crate) async fn sum_expense_by_period_and_category<C: ConnectionTrait>(
+    db: &C,
+    granularity: TimeGranularity,
+    week_start: u32,
+    start: chrono::NaiveDate,
+    end: chrono::NaiveDate,
+) -> Result<HashMap<(String, String), Decimal>, AppError> {
+    use sea_orm::QuerySelect;
+
+    #[derive(Debug, FromQueryResult)]
+    struct PeriodTotal {
+        period: String,
+        category_id: String,
+        total: Option<i64>,
+    }
+
+    let base_currency = settings_service::get_base_currency(db).await?;
+    let condition = Condition::all()
+        .add(expense_record::Column::RecordType.eq(RecordType::Expense))
+        .add(expense_record::Column::OccurredAt.gte(local_midnight_utc(start)))
+        .add(expense_record::Column::OccurredAt.lt(local_midnight_utc(end)))
+        .add(has_exchange_rate(&base_currency));
+
+    let period_expr = bucket_period_expr(granularity, week_start);
+    let rows = active_records()
+        .select_only()
+        .column_as(period_expr.clone(), "period")
+        .column(expense_record::Column::CategoryId)
+        .column_as(
+            sum_converted_by_type(RecordType::Expense, &base_currency),
+            "total",
+        )
+        .filter(condition)
+        .group_by(period_expr)
+        .group_by(expense_record::Column::CategoryId)
+        .into_model::<PeriodTotal>()
+        .all(db)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ((row.period, row.category_id), from_cents(row.total)))
+        .collect())
+}
+
+/// 单个分类在时间范围内的汇总，金额为本位币
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryBreakdown {
+    pub category_id: String,
+    pub name: String,
+    /// 默认分类的翻译 key，用于按语言输出名称
+    #[serde(skip)]
+    pub name_key: Option<String>,
+    pub icon: String,
+    pub color: String,
+    pub total: Decimal,
+    pub count: i64,
+    /// 占同类型全部分类合计的百分比
+    pub percentage: Decimal,
+}
+
+/// 分类汇总结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryBreakdownReport {
+    pub categories: Vec<CategoryBreakdown>,
+    /// 缺少汇率、未计入汇总的记录币种
+    pub missing_rates: Vec<String>,
+}
+
+// 按分类汇总收入或支出，金额从高到低排列；roll_up 时子分类并入顶级分类
+pub async fn get_category_breakdown(
+    db: &DatabaseConnection,
+    record_type: RecordType,
+    start_date: Option<chrono::DateTime<chrono::Utc>>,
+    end_date: Option<chrono::DateTime<chrono::Utc>>,
+    roll_up: bool,
+) -> Result<CategoryBreakdownReport, AppError> {
+    use sea_orm::{JoinType, QuerySelect, RelationTrait};
+
+    #[derive(Debug, FromQueryResult)]
+    struct BreakdownRow {
+        category_id: String,
+        name: String,
+        name_key: Option<String>,
+        icon: String,
+        color: String,
+        total: i64,
+        count: i64,
+    }
+
+    // 收入和支出分别计算占比，转账没有分类
+    if record_type == RecordType::Transfer {
+        return Err(AppError::validation(
+            "record_type",
+            "Record type must be expense or income",
+        ));
+    }
+
+    let condition = range_condition(Some(record_type), start_date, end_date);
+    let base_currency = settings_service::get_base_currency(db).await?;
+    let missing_rates = missing_exchange_rates(db, condition.clone(), &base_currency).await?;
+    let condition = condition.add(has_exchange_rate(&base_currency));
+
+    let total = sum_cents(converted_amount(&base_currency));
+
+    let mut rows = active_records()
+        .select_only()
+        .column(expense_record::Column::CategoryId)
+        .column(category::Column::Name)
+        .column(category::Column::NameKey)
+        .column(category::Column::Icon)
+        .column(category::Column::Color)
+        .column_as(total.clone(), "total")
+        .column_as(expense_record::Column::Id.count(), "count")
+        .join(
+            JoinType::InnerJoin,
+            expense_record::Relation::Category.def(),
+        )
+        .filter(condition)
+        .group_by(expense_record::Column::CategoryId)
+        .order_by_desc(total)
+        .into_model::<BreakdownRow>()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|row| CategoryBreakdown {
+            category_id: row.category_id,
+            name: row.name,
+            name_key: row.name_key,
+            icon: row.icon,
+            color: row.color,
+            total: from_cents(Some(row.total)),
+            count: row.count,
+            percentage: Decimal::ZERO,
+        })
+        .collect::<Vec<_>>();
+
+    if roll_up {
+        rows = roll_up_breakdown(db, rows).await?;
+    }
+
+    let grand_total: Decimal = rows.iter().map(|r| r.total).sum();
+    for row in rows.iter_mut() {
+        if !grand_total.is_zero() {
+            row.percentage = (row.total / grand_total * Decimal::ONE_HUNDRED).round_dp(2);
+        }
+    }
+
+    Ok(CategoryBreakdownReport {
+        categories: rows,
+        missing_rates,
+    })
+}
+
+// 把子分类的汇总合并到顶级分类上
+async fn roll_up_breakdown(
+    db: &DatabaseConnection,
+    rows: Vec<CategoryBreakdown>,
+) -> Result<Vec<CategoryBreakdown>, AppError> {
+    let roots = category_service::get_root_category_ids(db).await?;
+
+    let mut merged: Vec<CategoryBreakdown> = Vec::new();
+    for row in rows {
+        let root_id = roots
+            .get(&row.category_id)
+            .cloned()
+            .unwrap_or_else(|| row.category_id.clone());
+
+        if let Some(existing) = merged.iter_mut().find(|r| r.category_id == root_id) {
+            existing.total += row.total;
+            existing.count += row.count;
+            continue;
+        }
+
+        let mut row = row;
+        if row.category_id != root_id {
+            if let Some(root) = category_service::get_category_by_id(db, &root_id).await? {
+                row.name = root.name;
+                row.name_key = root.name_key;
+                row.icon = root.icon;
+                row.color = root.color;
+            }
+            row.category_id = root_id;
+        }
+        merged.push(row);
+    }
+
+    merged.sort_by_key(|r| std::cmp::Reverse(r.total));
+    Ok(merged)
+}
+
+/// 单个标签在时间范围内的汇总，金额为本位币
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagBreakdown {
+    pub tag_id: i32,
+    pub name: String,
+    pub total: Decimal,
+    pub count: i64,
+    /// 占范围内同类型全部记录合计的百分比；一条记录可带多个标签，各标签之和可能超过 100
+    pub percentage: Decimal,
+}
+
+/// 标签汇总结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagBreakdownReport {
+    pub tags: Vec<TagBreakdown>,
+    /// 缺少汇率、未计入汇总的记录币种
+    pub missing_rates: Vec<String>,
+}
+
+// 按标签汇总收入或支出，金额从高到低排列；没有标签的记录不计入任何标签
+pub async fn get_tag_breakdown(
+    db: &DatabaseConnection,
+    record_type: RecordType,
+    start_date: Option<chrono::DateTime<chrono::Utc>>,
+    end_date: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<TagBreakdownReport, AppError> {
+    use sea_orm::{JoinType, QuerySelect, RelationTrait};
+
+    #[derive(Debug, FromQueryResult)]
+    struct BreakdownRow {
+        tag_id: i32,
+        name: String,
+        total: i64,
+        count: i64,
+    }
+
+    // 与分类汇总一致，收入和支出分别计算占比
+    if record_type == RecordType::Transfer {
+        return Err(AppError::validation(
+            "record_type",
+            "Record type must be expense or income",
+        ));
+    }
+
+    let condition = range_condition(Some(record_type), start_date, end_date);
+    let base_currency = settings_service::get_base_currency(db).await?;
+    let missing_rates = missing_exchange_rates(db, condition.clone(), &base_currency).await?;
+    let condition = condition.add(has_exchange_rate(&base_currency));
+
+    let total = sum_cents(converted_amount(&base_currency));
+
+    let mut rows = active_records()
+        .select_only()
+        .column_as(tag::Column::Id, "tag_id")
+        .column(tag::Column::Name)
+        .column_as(total.clone(), "total")
+        .column_as(expense_record::Column::Id.count(), "count")
+        .join(
+            JoinType::InnerJoin,
+            expense_record::Relation::RecordTag.def(),
+        )
+        .join(JoinType::InnerJoin, record_tag::Relation::Tag.def())
+        .filter(condition.clone())
+        .group_by(tag::Column::Id)
+        .order_by_desc(total.clone())
+        .into_model::<BreakdownRow>()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|row| TagBreakdown {
+            tag_id: row.tag_id,
+            name: row.name,
+            total: from_cents(Some(row.total)),
+            count: row.count,
+            percentage: Decimal::ZERO,
+        })
+        .collect::<Vec<_>>();
+
+    let grand_total: Option<i64> = active_records()
+        .select_only()
+        .column_as(total, "total")
+        .filter(condition)
+        .into_tuple()
+        .one(db)
+        .await?
+        .flatten();
+    let grand_total = from_cents(grand_total);
+
+    for row in rows.iter_mut() {
+        if !grand_total.is_zero() {
+            row.percentage = (row.total / grand_total * Decimal::ONE_HUNDRED).round_dp(2);
+        }
+    }
+
+    Ok(TagBreakdownReport {
+        tags: rows,
+        missing_rates,
+    })
+}
+
+/// 时间序列的分桶粒度
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeGranularity {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// 时间序列中的一个分桶，金额为本位币
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimeBucket {
+    /// 分桶标识：日 `2024-01-31`、周（周首日）`2024-01-29`、月 `2024-01`、年 `2024`
+    pub period: String,
+    /// 分桶起始日期（本地时间）
+    pub start_date: chrono::NaiveDate,
+    pub income_total: Decimal,
+    pub expense_total: Decimal,
+}
+
+/// 时间序列结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimeSeries {
+    pub buckets: Vec<TimeBucket>,
+    /// 缺少汇率、未计入汇总的记录币种
+    pub missing_rates: Vec<String>,
+}
+
+// 分桶起始日期
+pub(crate) fn bucket_start(
+    date: chrono::NaiveDate,
+    granularity: TimeGranularity,
+    week_start: u32,
+) -> chrono::NaiveDate {
+    use chrono::Datelike;
+
+    match granularity {
+        TimeGranularity::Day => date,
+        TimeGranularity::Week => {
+            let offset = (date.weekday().num_days_from_sunday() + 7 - week_start) % 7;
+            date - chrono::Duration::days(offset as i64)
+        }
+        TimeGranularity::Month => date.with_day(1).unwrap_or(date),
+        TimeGranularity::Year => date.with_ordinal(1).unwrap_or(date),
+    }
+}
+
+pub(crate) fn next_bucket_start(
+    start: chrono::NaiveDate,
+    granularity: TimeGranularity,
+) -> chrono::NaiveDate {
+    match granularity {
+        TimeGranularity::Day => start + chrono::Duration::days(1),
+        TimeGranularity::Week => start + chrono::Duration::days(7),
+        TimeGranularity::Month => start + chrono::Months::new(1),
+        TimeGranularity::Year => start + chrono::Months::new(12),
+    }
+}
+
+pub(crate) fn bucket_period(start: chrono::NaiveDate, granularity: TimeGranularity) -> String {
+    match granularity {
+        TimeGranularity::Day | TimeGranularity::Week => start.format("%Y-%m-%d").to_string(),
+        TimeGranularity::Month => start.format("%Y-%m").to_string(),
+        TimeGranularity::Year => start.format("%Y").to_string(),
+    }
+}
+
+// 与 bucket_period 对应的 SQL 表达式，按本机时区换算
+fn bucket_period_expr(granularity: TimeGranularity, week_start: u32) -> SimpleExpr {
+    match granularity {
+        TimeGranularity::Day => Expr::cust("strftime('%Y-%m-%d', \"occurred_at\", 'localtime')"),
+        TimeGranularity::Week => Expr::cust(format!(
+            "date(\"occurred_at\", 'localtime', '-' || \
+             ((CAST(strftime('%w', \"occurred_at\", 'localtime') AS INTEGER) + 7 - {}) % 7) \
+             || ' days')",
+            week_start
+        )),
+        TimeGranularity::Month => Expr::cust("strftime('%Y-%m', \"occurred_at\", 'localtime')"),
+        TimeGranularity::Year => Expr::cust("strftime('%Y', \"occurred_at\", 'localtime')"),
+    }
+}
+
+// 本地日期零点对应的 UTC 时间
+pub(crate) fn local_midnight_utc(date: chrono::NaiveDate) -> chrono::DateTime<chrono::Utc> {
+    use chrono::TimeZone;
+
+    let midnight = date.and_time(chrono::NaiveTime::MIN);
+    chrono::Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|t| t.with_timezone(&chrono::Utc))
+        .unwrap_or_else(|| chrono::Utc.from_utc_datetime(&midnight))
+}
+
+// 按日/周/月/年汇总收支，空分桶补零；日期均为本机时区下的本地日期，含首尾两天
+pub async fn get_time_series(
+    db: &DatabaseConnection,
+    granularity: TimeGranularity,
+    start_date: chrono::NaiveDate,
+    end_date: chrono::NaiveDate,
+    week_start: u32,
+) -> Result<TimeSeries, AppError> {
+    use sea_orm::QuerySelect;
+    use std::collections::HashMap;
+
+    #[derive(Debug, FromQueryResult)]
+    struct BucketResult {
+        period: String,
+        income_total: Option<i64>,
+        expense_total: Option<i64>,
+    }
+
+    if week_start > 6 {
+        return Err(AppError::validation(
+            "week_start",
+            "Week start must be between 0 (Sunday) and 6 (Saturday)",
+        ));
+    }
+
+    if end_date < start_date {
+        return Ok(TimeSeries {
+            buckets: Vec::new(),
+            missing_rates: Vec::new(),
+        });
+    }
+
+    let condition = Condition::all()
+        .add(expense_record::Column::OccurredAt.gte(local_midnight_utc(start_date)))
+        .add(
+            expense_record::Column::OccurredAt
+                .lt(local_midnight_utc(end_date + chrono::Duration::days(1))),
+        );
+    let base_currency = settings_service::get_base_currency(db).await?;
+    let missing_rates = missing_exchange_rates(db, condition.clone(), &base_currency).await?;
+    let condition = condition.add(has_exchange_rate(&base_currency));
+
+    let period_expr = bucket_period_expr(granularity, week_start);
+    let rows = active_records()
+        .select_only()
+        .column_as(period_expr.clone(), "period")
+        .column_as(
+            sum_converted_by_type(RecordType::Income, &base_currency),
+            "income_total",
+        )
+        .column_as(
+            sum_converted_by_type(RecordType::Expense, &base_currency),
+            "expense_total",
+        )
+        .filter(condition)
+        .group_by(period_expr)
+        .into_model::<BucketResult>()
+        .all(db)
+        .await?;
+
+    let mut totals: HashMap<String, BucketResult> =
+        rows.into_iter().map(|r| (r.period.clone(), r)).collect();
+
+    let mut buckets = Vec::new();
+    let mut cursor = bucket_start(start_date, granularity, week_start);
+    while cursor <= end_date {
+        let period = bucket_period(cursor, granularity);
+        let (income_total, expense_total) = totals
+            .remove(&period)
+            .map(|r| (from_cents(r.income_total), from_cents(r.expense_total)))
+            .unwrap_or_default();
+
+        buckets.push(TimeBucket {
+            period,
+            start_date: cursor,
+            income_total,
+            expense_total,
+        });
+        cursor = next_bucket_start(cursor, granularity);
+    }
+
+    Ok(TimeSeries {
+        buckets,
+        missing_rates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_connection;
+    use crate::database::test_util::{account, invalid_fields, record_data, time};
+    use sea_orm::{ConnectionTrait, Statement};
+
+    async fn create(
+        db: &DatabaseConnection,
+        category_id: &str,
+        note: Option<&str>,
+        occurred_at: &str,
+        tags: &[&str],
+    ) -> expense_record::Model {
+        create_record(
+            db,
+            ExpenseRecordData {
+                note: note.map(String::from),
+                tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+                ..record_data(
+                    RecordType::Expense,
+                    category_id,
+                    Decimal::new(10, 0),
+                    Some(occurred_at),
+                )
+            },
+        )
+        .await
+        .unwrap()
+        .record
+    }
+
+    fn record_ids(page: RecordPage) -> Vec<i32> {
+        page.records
+            .into_iter()
+            .map(|record| record.record.id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn records_are_filtered_and_ordered_by_occurred_at() {
+        let db = test_connection().await;
+        // 补记的记录按发生时间归入当天，而不是创建时间
+        let backdated = create(&db, "food", None, "2026-09-30T12:00:00Z", &[]).await;
+        let current = create(&db, "food", None, "2026-10-01T12:00:00Z", &[]).await;
+        assert!(backdated.created_at > backdated.occurred_at);
+
+        let filter = RecordFilter {
+            start_date: Some(time("2026-10-01T00:00:00Z")),
+            end_date: Some(time("2026-10-01T23:59:59Z")),
+            ..Default::default()
+        };
+        let page = get_records(&db, Some(filter)).await.unwrap();
+        assert_eq!(record_ids(page), [current.id]);
+
+        let page = get_records(&db, None).await.unwrap();
+        assert_eq!(record_ids(page), [current.id, backdated.id]);
+
+        // 修改发生时间后按新的时间排序
+        let updated = update_record(
+            &db,
+            backdated.id,
+            record_data(
+                RecordType::Expense,
+                "food",
+                Decimal::new(10, 0),
+                Some("2026-10-02T08:00:00Z"),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.record.occurred_at, time("2026-10-02T08:00:00Z"));
+        let page = get_records(&db, None).await.unwrap();
+        assert_eq!(record_ids(page), [backdated.id, current.id]);
+    }
+
+    #[tokio::test]
+    async fn statistics_report_totals_range() {
+        let db = test_connection().await;
+        for (record_type, category_id, amount, occurred_at) in [
+            (RecordType::Expense, "food", 3000, "2026-10-02T12:00:00Z"),
+            (
+                RecordType::Expense,
+                "transport",
+                6000,
+                "2026-10-05T12:00:00Z",
+            ),
+            (RecordType::Income, "salary", 100000, "2026-10-08T12:00:00Z"),
+            // 范围之外
+            (RecordType::Expense, "food", 50000, "2026-09-30T12:00:00Z"),
+        ] {
+            create_record(
+                &db,
+                record_data(
+                    record_type,
+                    category_id,
+                    Decimal::new(amount, 2),
+                    Some(occurred_at),
+                ),
+            )
+            .await
+            .unwrap();
+        }
+
+        let report = get_statistics(
+            &db,
+            None,
+            Some(time("2026-10-01T00:00:00Z")),
+            Some(time("2026-10-10T00:00:00Z")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.currency, "CNY");
+        assert_eq!(report.income_total, Decimal::new(1000, 0));
+        assert_eq!(report.expense_total, Decimal::new(90, 0));
+        assert_eq!(report.net_balance, Decimal::new(910, 0));
+        assert_eq!(report.record_count, 3);
+        // 范围共 9 天
+        assert_eq!(report.average_per_day, Decimal::new(10, 0));
+        assert_eq!(
+            report.max_record.map(|record| record.category_id),
+            Some(Some("salary".to_string()))
+        );
+
+        let report = get_statistics(
+            &db,
+            Some(RecordType::Expense),
+            Some(time("2026-10-01T00:00:00Z")),
+            Some(time("2026-10-10T00:00:00Z")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.income_total, Decimal::ZERO);
+        assert_eq!(report.record_count, 2);
+    }
+
+    #[tokio::test]
+    async fn category_breakdown_sums_and_shares_per_type() {
+        let db = test_connection().await;
+        for (record_type, category_id, amount) in [
+            (RecordType::Expense, "food", 30),
+            (RecordType::Expense, "food", 45),
+            (RecordType::Expense, "transport", 25),
+            (RecordType::Income, "salary", 1000),
+        ] {
+            create_record(
+                &db,
+                record_data(
+                    record_type,
+                    category_id,
+                    Decimal::new(amount, 0),
+                    Some("2026-10-02T12:00:00Z"),
+                ),
+            )
+            .await
+            .unwrap();
+        }
+
+        let report = get_category_breakdown(&db, RecordType::Expense, None, None, false)
+            .await
+            .unwrap();
+        let rows: Vec<_> = report
+            .categories
+            .iter()
+            .map(|row| {
+                (
+                    row.category_id.as_str(),
+                    row.total,
+                    row.count,
+                    row.percentage,
+                )
+            })
+            .collect();
+        // 收入不计入支出的占比
+        assert_eq!(
+            rows,
+            [
+                ("food", Decimal::new(75, 0), 2, Decimal::new(75, 0)),
+                ("transport", Decimal::new(25, 0), 1, Decimal::new(25, 0)),
+            ]
+        );
+        assert_eq!(report.categories[0].name, "餐饮");
+
+        let result = get_category_breakdown(&db, RecordType::Transfer, None, None, false).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    // 本机时区下 date 当天 hour 点的 RFC 3339 时间
+    fn local_time(date: &str, hour: i64) -> String {
+        let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        (local_midnight_utc(date) + chrono::Duration::hours(hour)).to_rfc3339()
+    }
+
+    #[tokio::test]
+    async fn time_series_buckets_by_local_date_and_fills_gaps() {
+        let db = test_connection().await;
+        for (record_type, category_id, amount, date, hour) in [
+            (RecordType::Expense, "food", 10, "2026-10-05", 23),
+            (RecordType::Expense, "food", 15, "2026-10-05", 0),
+            (RecordType::Income, "salary", 100, "2026-10-07", 20),
+            (RecordType::Expense, "food", 20, "2026-10-11", 12),
+        ] {
+            create_record(
+                &db,
+                record_data(
+                    record_type,
+                    category_id,
+                    Decimal::new(amount, 0),
+                    Some(&local_time(date, hour)),
+                ),
+            )
+            .await
+            .unwrap();
+        }
+
+        let start = chrono::NaiveDate::from_ymd_opt(2026, 10, 5).unwrap();
+        let end = chrono::NaiveDate::from_ymd_opt(2026, 10, 11).unwrap();
+        let series = get_time_series(&db, TimeGranularity::Day, start, end, 1)
+            .await
+            .unwrap();
+        let days: Vec<_> = series
+            .buckets
+            .iter()
+            .map(|bucket| {
+                (
+                    bucket.period.as_str(),
+                    bucket.income_total,
+                    bucket.expense_total,
+                )
+            })
+            .collect();
+        assert_eq!(days.len(), 7);
+        assert_eq!(days[0], ("2026-10-05", Decimal::ZERO, Decimal::new(25, 0)));
+        assert_eq!(days[1], ("2026-10-06", Decimal::ZERO, Decimal::ZERO));
+        assert_eq!(days[2], ("2026-10-07", Decimal::new(100, 0), Decimal::ZERO));
+        assert_eq!(days[6], ("2026-10-11", Decimal::ZERO, Decimal::new(20, 0)));
+
+        // 2026-10-05 是周一：周一开始时一周即全部记录，周日开始时 10-11 属于下一周
+        let series = get_time_series(&db, TimeGranularity::Week, start, end, 1)
+            .await
+            .unwrap();
+        assert_eq!(series.buckets.len(), 1);
+        assert_eq!(series.buckets[0].expense_total, Decimal::new(45, 0));
+
+        let series = get_time_series(&db, TimeGranularity::Week, start, end, 0)
+            .await
+            .unwrap();
+        let weeks: Vec<_> = series
+            .buckets
+            .iter()
+            .map(|bucket| (bucket.period.as_str(), bucket.expense_total))
+            .collect();
+        assert_eq!(
+            weeks,
+            [
+                ("2026-10-04", Decimal::new(25, 0)),
+                ("2026-10-11", Decimal::new(20, 0)),
+            ]
+        );
+
+        let result = get_time_series(&db, TimeGranularity::Day, start, end, 7).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn cursor_pagination_walks_all_records_once() {
+        let db = test_connection().await;
+        // 两组发生时间相同的记录，翻页需要按 id 区分
+        let mut expected = Vec::new();
+        for occurred_at in [
+            "2026-10-01T10:00:00Z",
+            "2026-10-02T10:00:00Z",
+            "2026-10-02T10:00:00Z",
+            "2026-10-03T10:00:00Z",
+            "2026-10-03T10:00:00Z",
+        ] {
+            expected.push(create(&db, "food", None, occurred_at, &[]).await);
+        }
+        expected.sort_by(|a, b| b.occurred_at.cmp(&a.occurred_at).then(b.id.cmp(&a.id)));
+        let expected: Vec<i32> = expected.into_iter().map(|record| record.id).collect();
+
+        let mut ids = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = get_records(
+                &db,
+                Some(RecordFilter {
+                    limit: Some(2),
+                    cursor,
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+            assert_eq!(page.total, 5);
+            assert_eq!(page.has_more, page.next_cursor.is_some());
+            ids.extend(page.records.iter().map(|record| record.record.id));
+            if !page.has_more {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+        assert_eq!(ids, expected);
+
+        let page = get_records(
+            &db,
+            Some(RecordFilter {
+                limit: Some(2),
+                offset: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(!page.has_more);
+        assert_eq!(record_ids(page), expected[4..]);
+    }
+
+    // 校验失败的字段名
+    #[tokio::test]
+    async fn record_validation_reports_each_field() {
+        let db = test_connection().await;
+
+        let result = create_record(
+            &db,
+            ExpenseRecordData {
+                note: Some("x".repeat(validation::MAX_NOTE_LENGTH + 1)),
+                ..record_data(
+                    RecordType::Expense,
+                    "salary",
+                    Decimal::ZERO,
+                    Some("2026-10-01T12:00:00Z"),
+                )
+            },
+        )
+        .await;
+        assert_eq!(invalid_fields(result), ["amount", "note", "category_id"]);
+
+        let result = create_record(
+            &db,
+            record_data(
+                RecordType::Expense,
+                "missing",
+                Decimal::new(1001, 3),
+                Some("2026-10-01T12:00:00Z"),
+            ),
+        )
+        .await;
+        assert_eq!(invalid_fields(result), ["amount", "category_id"]);
+
+        // 最多两位小数，备注恰好达到上限
+        let result = create_record(
+            &db,
+            ExpenseRecordData {
+                note: Some("x".repeat(validation::MAX_NOTE_LENGTH)),
+                ..record_data(
+                    RecordType::Expense,
+                    "food",
+                    Decimal::new(1010, 3),
+                    Some("2026-10-01T12:00:00Z"),
+                )
+            },
+        )
+        .await;
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn record_type_rejects_unknown_casing() {
+        let record_type: Result<RecordType, _> = serde_json::from_str(r#""Expense""#);
+        assert!(record_type.is_err());
+    }
+
+    #[tokio::test]
+    async fn trashed_records_are_hidden_until_restored() {
+        let db = test_connection().await;
+        let kept = create(&db, "food", None, "2026-10-01T12:00:00Z", &[]).await;
+        let trashed = create(&db, "food", None, "2026-10-02T12:00:00Z", &[]).await;
+
+        delete_record(&db, trashed.id).await.unwrap();
+        assert!(matches!(
+            delete_record(&db, trashed.id).await,
+            Err(AppError::NotFound { .. })
+        ));
+        assert_eq!(record_ids(get_records(&db, None).await.unwrap()), [kept.id]);
+        assert!(get_record_by_id(&db, trashed.id).await.unwrap().is_none());
+        let report = get_statistics(&db, None, None, None).await.unwrap();
+        assert_eq!(report.expense_total, Decimal::new(10, 0));
+
+        let trash = get_trash(&db).await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].id, trashed.id);
+
+        restore_record(&db, trashed.id).await.unwrap();
+        assert_eq!(
+            record_ids(get_records(&db, None).await.unwrap()),
+            [trashed.id, kept.id]
+        );
+
+        // 只能彻底删除回收站中的记录
+        assert!(matches!(
+            purge_record(&db, kept.id).await,
+            Err(AppError::NotFound { .. })
+        ));
+        delete_record(&db, kept.id).await.unwrap();
+        assert_eq!(purge_expired_trash(&db, 1).await.unwrap(), 0);
+        assert_eq!(purge_expired_trash(&db, -1).await.unwrap(), 1);
+        assert!(get_trash(&db).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn breakdown_rolls_subcategories_up_to_parents() {
+        let db = test_connection().await;
+        create(&db, "food", None, "2026-10-01T12:00:00Z", &[]).await;
+        create(&db, "fruits", None, "2026-10-01T12:00:00Z", &[]).await;
+        create(&db, "snacks", None, "2026-10-01T12:00:00Z", &[]).await;
+        create(&db, "transport", None, "2026-10-01T12:00:00Z", &[]).await;
+
+        let report = get_category_breakdown(&db, RecordType::Expense, None, None, false)
+            .await
+            .unwrap();
+        assert_eq!(report.categories.len(), 4);
+
+        let report = get_category_breakdown(&db, RecordType::Expense, None, None, true)
+            .await
+            .unwrap();
+        let rows: Vec<_> = report
+            .categories
+            .iter()
+            .map(|row| (row.category_id.as_str(), row.name.as_str(), row.count))
+            .collect();
+        assert_eq!(rows, [("food", "餐饮", 3), ("transport", "交通", 1)]);
+        assert_eq!(report.categories[0].percentage, Decimal::new(75, 0));
+    }
+
+    #[tokio::test]
+    async fn breakdown_names_follow_locale_for_default_categories() {
+        let db = test_connection().await;
+        create(&db, "snacks", None, "2026-10-01T12:00:00Z", &[]).await;
+        crate::database::category_service::create_category(
+            &db,
+            crate::database::category_service::CategoryData {
+                category_id: "cat_food".to_string(),
+                name: "猫粮".to_string(),
+                icon: "pets".to_string(),
+                color: "#000000".to_string(),
+                category_type: crate::entities::sea_orm_active_enums::CategoryType::Expense,
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap();
+        create(&db, "cat_food", None, "2026-10-01T12:00:00Z", &[]).await;
+
+        let mut report = get_category_breakdown(&db, RecordType::Expense, None, None, true)
+            .await
+            .unwrap();
+        crate::database::i18n::localize_category_breakdown(
+            &mut report.categories,
+            crate::database::i18n::Locale::En,
+        );
+        let mut names: Vec<_> = report
+            .categories
+            .iter()
+            .map(|row| row.name.as_str())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["Food", "猫粮"]);
+    }
+
+    fn transfer_data(from_account_id: i32, to_account_id: i32) -> TransferData {
+        TransferData {
+            from_account_id,
+            to_account_id,
+            amount: Decimal::new(100, 0),
+            fee: None,
+            note: None,
+            occurred_at: Some(
+                chrono::DateTime::parse_from_rfc3339("2026-10-01T12:00:00Z").unwrap(),
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn transfer_validation_reports_fee_and_accounts() {
+        let db = test_connection().await;
+        let wallet = account(&db, "钱包", "CNY").await;
+        let card = account(&db, "外币卡", "USD").await;
+
+        let result = create_transfer(
+            &db,
+            TransferData {
+                fee: Some(Decimal::new(1001, 3)),
+                ..transfer_data(wallet, wallet)
+            },
+        )
+        .await;
+        match result {
+            Err(AppError::Validation(fields)) => {
+                let fields: Vec<_> = fields
+                    .iter()
+                    .map(|field| (field.field.as_str(), field.message.as_str()))
+                    .collect();
+                assert_eq!(
+                    fields,
+                    [
+                        ("fee", "Fee must have at most 2 decimal places"),
+                        ("to_account_id", "Cannot transfer to the same account"),
+                    ]
+                );
+            }
+            other => panic!("expected validation error, got {:?}", other),
+        }
+
+        let result = create_transfer(
+            &db,
+            TransferData {
+                fee: Some(Decimal::NEGATIVE_ONE),
+                ..transfer_data(wallet, card)
+            },
+        )
+        .await;
+        assert_eq!(invalid_fields(result), ["fee", "to_account_id"]);
+
+        let result = create_transfer(&db, transfer_data(wallet, 999)).await;
+        assert_eq!(invalid_fields(result), ["to_account_id"]);
+    }
+
+    #[tokio::test]
+    async fn transfers_are_listed_for_both_accounts_and_excluded_from_statistics() {
+        let db = test_connection().await;
+        let wallet = account(&db, "钱包", "CNY").await;
+        let card = account(&db, "银行卡", "CNY").await;
+        let other = account(&db, "备用金", "CNY").await;
+
+        let transfer = create_transfer(
+            &db,
+            TransferData {
+                fee: Some(Decimal::new(2, 0)),
+                ..transfer_data(wallet, card)
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(transfer.currency, "CNY");
+
+        for account_id in [wallet, card] {
+            let page = get_records(
+                &db,
+                Some(RecordFilter {
+                    account_id: Some(account_id),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+            assert_eq!(record_ids(page), [transfer.id]);
+        }
+        let page = get_records(
+            &db,
+            Some(RecordFilter {
+                account_id: Some(other),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.total, 0);
+
+        let report = get_statistics(&db, None, None, None).await.unwrap();
+        assert_eq!(report.income_total, Decimal::ZERO);
+        assert_eq!(report.expense_total, Decimal::ZERO);
+        assert_eq!(report.record_count, 0);
+
+        let balances = account_service::get_account_balances(&db).await.unwrap();
+        let balances: Vec<_> = balances
+            .iter()
+            .map(|balance| (balance.account.id, balance.balance))
+            .collect();
+        assert_eq!(
+            balances,
+            [
+                (wallet, Decimal::new(-102, 0)),
+                (card, Decimal::new(100, 0)),
+                (other, Decimal::ZERO),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn statistics_convert_with_latest_rate_and_report_missing_rates() {
+        let db = test_connection().await;
+        for (currency, quote_currency, rate_date, rate) in [
+            ("USD", "CNY", "2026-10-01", Decimal::new(7, 0)),
+            ("USD", "CNY", "2026-10-10", Decimal::new(8, 0)),
+            // 只有反向汇率时取倒数
+            ("CNY", "HKD", "2026-10-01", Decimal::new(125, 2)),
+        ] {
+            crate::database::exchange_rate_service::set_exchange_rate(
+                &db,
+                crate::database::exchange_rate_service::ExchangeRateData {
+                    currency: currency.into(),
+                    quote_currency: quote_currency.into(),
+                    rate_date: chrono::NaiveDate::parse_from_str(rate_date, "%Y-%m-%d").unwrap(),
+                    rate,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        for (currency, amount, occurred_at) in [
+            ("USD", 10, "2026-10-05T12:00:00Z"),
+            ("USD", 10, "2026-10-12T12:00:00Z"),
+            ("HKD", 100, "2026-10-05T12:00:00Z"),
+            ("CNY", 5, "2026-10-05T12:00:00Z"),
+            // 早于所有汇率、没有任何汇率的记录不计入
+            ("USD", 1000, "2026-09-30T12:00:00Z"),
+            ("JPY", 1000, "2026-10-05T12:00:00Z"),
+        ] {
+            create_record(
+                &db,
+                ExpenseRecordData {
+                    currency: Some(currency.into()),
+                    ..record_data(
+                        RecordType::Expense,
+                        "food",
+                        Decimal::new(amount, 0),
+                        Some(occurred_at),
+                    )
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let report = get_statistics(&db, None, None, None).await.unwrap();
+        assert_eq!(report.currency, "CNY");
+        assert_eq!(report.expense_total, Decimal::new(235, 0));
+        assert_eq!(report.record_count, 4);
+        assert_eq!(report.missing_rates, ["JPY", "USD"]);
+
+        let report = get_category_breakdown(&db, RecordType::Expense, None, None, false)
+            .await
+            .unwrap();
+        assert_eq!(report.categories[0].total, Decimal::new(235, 0));
+        assert_eq!(report.missing_rates, ["JPY", "USD"]);
+    }
+
+    async fn tagged_ids(db: &DatabaseConnection, filter: RecordFilter) -> Vec<i32> {
+        let mut ids = record_ids(get_records(db, Some(filter)).await.unwrap());
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn records_filter_by_any_or_all_tags() {
+        let db = test_connection().await;
+        let both = create(&db, "food", None, "2026-10-01T12:00:00Z", &["work", "trip"]).await;
+        let work = create(
+            &db,
+            "food",
+            None,
+            "2026-10-02T12:00:00Z",
+            &[" work ", "work"],
+        )
+        .await;
+        let trip = create(&db, "food", None, "2026-10-03T12:00:00Z", &["trip"]).await;
+        create(&db, "food", None, "2026-10-04T12:00:00Z", &[]).await;
+
+        let filter = |tags_any: &[&str], tags_all: &[&str]| RecordFilter {
+            tags_any: (!tags_any.is_empty())
+                .then(|| tags_any.iter().map(|tag| tag.to_string()).collect()),
+            tags_all: (!tags_all.is_empty())
+                .then(|| tags_all.iter().map(|tag| tag.to_string()).collect()),
+            ..Default::default()
+        };
+        assert_eq!(
+            tagged_ids(&db, filter(&["work", "trip"], &[])).await,
+            [both.id, work.id, trip.id]
+        );
+        assert_eq!(
+            tagged_ids(&db, filter(&[], &["work", "trip"])).await,
+            [both.id]
+        );
+        assert_eq!(
+            tagged_ids(&db, filter(&["trip"], &["work"])).await,
+            [both.id]
+        );
+
+        // 名称去掉空白并去重
+        let record = get_record_by_id(&db, work.id).await.unwrap().unwrap();
+        let record = with_tags(&db, vec![record]).await.unwrap();
+        assert_eq!(record[0].tags, ["work"]);
+    }
+
+    #[tokio::test]
+    async fn updates_keep_tags_unless_given() {
+        let db = test_connection().await;
+        let record = create(&db, "food", None, "2026-10-01T12:00:00Z", &["work"]).await;
+
+        let data = record_data(
+            RecordType::Expense,
+            "food",
+            Decimal::new(20, 0),
+            Some("2026-10-01T12:00:00Z"),
+        );
+        let updated = update_record(&db, record.id, data).await.unwrap();
+        assert_eq!(updated.tags, ["work"]);
+
+        let data = ExpenseRecordData {
+            tags: Some(vec![]),
+            ..record_data(
+                RecordType::Expense,
+                "food",
+                Decimal::new(20, 0),
+                Some("2026-10-01T12:00:00Z"),
+            )
+        };
+        let updated = update_record(&db, record.id, data).await.unwrap();
+        assert!(updated.tags.is_empty());
+    }
+
+    #[tokio::test]
+    async fn tag_breakdown_shares_are_per_record_type() {
+        let db = test_connection().await;
+        create(&db, "food", None, "2026-10-01T12:00:00Z", &["work", "trip"]).await;
+        create(&db, "food", None, "2026-10-02T12:00:00Z", &["work"]).await;
+        create(&db, "food", None, "2026-10-03T12:00:00Z", &[]).await;
+        create_record(
+            &db,
+            ExpenseRecordData {
+                tags: Some(vec!["work".into()]),
+                ..record_data(
+                    RecordType::Income,
+                    "salary",
+                    Decimal::new(1000, 0),
+                    Some("2026-10-04T12:00:00Z"),
+                )
+            },
+        )
+        .await
+        .unwrap();
+
+        let report = get_tag_breakdown(&db, RecordType::Expense, None, None)
+            .await
+            .unwrap();
+        let tags: Vec<_> = report
+            .tags
+            .iter()
+            .map(|tag| (tag.name.as_str(), tag.total, tag.count, tag.percentage))
+            .collect();
+        // 没有标签的记录计入分母，各标签占比之和可能超过 100
+        assert_eq!(
+            tags,
+            [
+                ("work", Decimal::new(20, 0), 2, Decimal::new(6667, 2)),
+                ("trip", Decimal::new(10, 0), 1, Decimal::new(3333, 2)),
+            ]
+        );
+
+        assert!(matches!(
+            get_tag_breakdown(&db, RecordType::Transfer, None, None).await,
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    async fn search_ids(db: &DatabaseConnection, query: &str) -> Vec<i32> {
+        search_records(db, query, None)
+            .await
+            .unwrap()
+            .records
+            .into_iter()
+            .map(|record| record.record.id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn search_index_follows_record_tag_and_category_changes() {
+        let db = test_connection().await;
+        let record = create(
+            &db,
+            "food",
+            Some("breakfast"),
+            "2026-10-01T10:00:00Z",
+            &["早餐"],
+        )
+        .await;
+
+        assert_eq!(search_ids(&db, "breakfast").await, [record.id]);
+        assert_eq!(search_ids(&db, "早餐").await, [record.id]);
+        // 默认分类按繁体和英文名称也能搜到
+        assert_eq!(search_ids(&db, "Food").await, [record.id]);
+
+        update_record(
+            &db,
+            record.id,
+            ExpenseRecordData {
+                note: Some("dinner".into()),
+                ..record_data(RecordType::Expense, "fruits", Decimal::new(10, 0), None)
+            },
+        )
+        .await
+        .unwrap();
+        assert!(search_ids(&db, "breakfast").await.is_empty());
+        assert_eq!(search_ids(&db, "dinner").await, [record.id]);
+        assert_eq!(search_ids(&db, "水果").await, [record.id]);
+        assert!(search_ids(&db, "Food").await.is_empty());
+
+        let tag = tag_service::get_tags(&db).await.unwrap().remove(0);
+        tag_service::update_tag(
+            &db,
+            tag.id,
+            tag_service::TagData {
+                name: "夜宵".into(),
+            },
+        )
+        .await
+        .unwrap();
+        assert!(search_ids(&db, "早餐").await.is_empty());
+        assert_eq!(search_ids(&db, "夜宵").await, [record.id]);
+
+        let fruits = category_service::get_category_by_id(&db, "fruits")
+            .await
+            .unwrap()
+            .unwrap();
+        category_service::update_category(
+            &db,
+            "fruits",
+            category_service::CategoryData {
+                category_id: fruits.category_id,
+                name: "鲜果".into(),
+                icon: fruits.icon,
+                color: fruits.color,
+                category_type: fruits.category_type,
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(search_ids(&db, "鲜果").await, [record.id]);
+        // 改名后不再索引默认分类的翻译
+        assert!(search_ids(&db, "Fruits").await.is_empty());
+
+        delete_record(&db, record.id).await.unwrap();
+        purge_record(&db, record.id).await.unwrap();
+        let remaining: Vec<i64> = db
+            .query_all(Statement::from_string(
+                db.get_database_backend(),
+                r#"SELECT "rowid" FROM "record_search""#,
+            ))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.try_get("", "rowid").unwrap())
+            .collect();
+        assert!(remaining.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fractional_totals_do_not_drift() {
+        let db = test_connection().await;
+        let account_id = account(&db, "钱包", "CNY").await;
+        // 十笔 0.1 按浮点数累加得到 0.9999999999999999
+        for day in 1..=10 {
+            create_record(
+                &db,
+                ExpenseRecordData {
+                    account_id: Some(account_id),
+                    tags: Some(vec!["work".into()]),
+                    ..record_data(
+                        RecordType::Expense,
+                        "food",
+                        Decimal::new(1, 1),
+                        Some(&format!("2026-10-{:02}T12:00:00Z", day)),
+                    )
+                },
+            )
+            .await
+            .unwrap();
+        }
+        let total = Decimal::ONE;
+
+        let report = get_statistics(&db, None, None, None).await.unwrap();
+        assert_eq!(report.expense_total, total);
+        assert_eq!(report.net_balance, -total);
+
+        let report = get_category_breakdown(&db, RecordType::Expense, None, None, false)
+            .await
+            .unwrap();
+        assert_eq!(report.categories[0].total, total);
+
+        let report = get_tag_breakdown(&db, RecordType::Expense, None, None)
+            .await
+            .unwrap();
+        assert_eq!(report.tags[0].total, total);
+
+        let series = get_time_series(
+            &db,
+            TimeGranularity::Month,
+            chrono::NaiveDate::from_ymd_opt(2026, 10, 1).unwrap(),
+            chrono::NaiveDate::from_ymd_opt(2026, 10, 31).unwrap(),
+            1,
+        )
+        .await
+        .unwrap();
+        assert_eq!(series.buckets[0].expense_total, total);
+
+        let balances = account_service::get_account_balances(&db).await.unwrap();
+        assert_eq!(balances[0].expense_total, total);
+        assert_eq!(balances[0].balance, -total);
+    }
 }
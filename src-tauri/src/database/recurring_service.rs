@@ -0,0 +1,459 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+use rust_decimal::Decimal;
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::database::record_service::{self, ExpenseRecordData};
+use crate::database::validation::Validator;
+use crate::entities::{
+    expense_record, recurring_rule, ExpenseRecord, RecordType, RecurrenceFrequency, RecurringRule,
+};
+use crate::error::AppError;
+
+// every_n_days 允许的最大间隔
+pub const MAX_INTERVAL_DAYS: i32 = 366;
+// 后台任务两次检查之间的最长间隔，系统休眠或调整时钟后也能及时补上
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecurringRuleData {
+    pub record_type: RecordType,
+    pub category_id: String,
+    pub account_id: Option<i32>,
+    pub amount: Decimal,
+    /// 币种，缺省时取账户币种，没有账户时取本位币
+    pub currency: Option<String>,
+    pub note: Option<String>,
+    pub frequency: RecurrenceFrequency,
+    /// every_n_days 的间隔天数
+    pub interval_days: Option<i32>,
+    /// monthly 的日期，缺省为开始日期的日
+    pub day_of_month: Option<i32>,
+    pub start_date: NaiveDate,
+    /// 最后一个可生成的日期，为空表示一直重复
+    pub end_date: Option<NaiveDate>,
+}
+
+pub async fn get_recurring_rules(
+    db: &DatabaseConnection,
+) -> Result<Vec<recurring_rule::Model>, AppError> {
+    Ok(RecurringRule::find()
+        .order_by_asc(recurring_rule::Column::Id)
+        .all(db)
+        .await?)
+}
+
+pub async fn get_recurring_rule_by_id(
+    db: &DatabaseConnection,
+    id: i32,
+) -> Result<Option<recurring_rule::Model>, AppError> {
+    Ok(RecurringRule::find_by_id(id).one(db).await?)
+}
+
+// 校验记录部分沿用 record_service 的规则，返回最终使用的币种
+async fn validate_rule(
+    db: &DatabaseConnection,
+    rule_data: &RecurringRuleData,
+) -> Result<String, AppError> {
+    let mut validator = Validator::new();
+
+    match rule_data.frequency {
+        RecurrenceFrequency::EveryNDays => validator.check(
+            rule_data
+                .interval_days
+                .is_some_and(|days| (1..=MAX_INTERVAL_DAYS).contains(&days)),
+            "interval_days",
+            format!("Interval must be 1-{} days", MAX_INTERVAL_DAYS),
+        ),
+        _ => validator.check(
+            rule_data.interval_days.is_none(),
+            "interval_days",
+            "Interval is only used for every_n_days",
+        ),
+    }
+    match rule_data.frequency {
+        RecurrenceFrequency::Monthly => validator.check(
+            rule_data
+                .day_of_month
+                .is_none_or(|day| (1..=31).contains(&day)),
+            "day_of_month",
+            "Day of month must be 1-31",
+        ),
+        _ => validator.check(
+            rule_data.day_of_month.is_none(),
+            "day_of_month",
+            "Day of month is only used for monthly",
+        ),
+    }
+    if let Some(end_date) = rule_data.end_date {
+        validator.check(
+            end_date >= rule_data.start_date,
+            "end_date",
+            "End date must not be before the start date",
+        );
+    }
+    validator.finish()?;
+
+    let record_data = ExpenseRecordData {
+        record_type: rule_data.record_type,
+        category_id: rule_data.category_id.clone(),
+        account_id: rule_data.account_id,
+        amount: rule_data.amount,
+        currency: rule_data.currency.clone(),
+        note: rule_data.note.clone(),
+        occurred_at: None,
+        tags: None,
+    };
+    record_service::validate_record(db, &record_data, None).await
+}
+
+// 创建后立即补上开始日期到今天之间的记录
+pub async fn create_recurring_rule(
+    db: &DatabaseConnection,
+    rule_data: RecurringRuleData,
+) -> Result<recurring_rule::Model, AppError> {
+    let currency = validate_rule(db, &rule_data).await?;
+
+    let now = chrono::Utc::now().into();
+    let rule = recurring_rule::ActiveModel {
+        record_type: Set(rule_data.record_type),
+        category_id: Set(rule_data.category_id),
+        account_id: Set(rule_data.account_id),
+        amount: Set(rule_data.amount),
+        currency: Set(currency),
+        note: Set(rule_data.note),
+        frequency: Set(rule_data.frequency),
+        interval_days: Set(rule_data.interval_days),
+        day_of_month: Set(rule_data.day_of_month),
+        start_date: Set(rule_data.start_date),
+        end_date: Set(rule_data.end_date),
+        materialized_until: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    };
+    let rule = rule.insert(db).await?;
+
+    let (rule, _) = materialize_rule(db, rule, chrono::Local::now().date_naive()).await?;
+
+    Ok(rule)
+}
+
+// 修改只影响之后生成的记录，已生成的记录保持不变
+pub async fn update_recurring_rule(
+    db: &DatabaseConnection,
+    id: i32,
+    rule_data: RecurringRuleData,
+) -> Result<recurring_rule::Model, AppError> {
+    let rule = get_recurring_rule_by_id(db, id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Recurring rule", id))?;
+
+    let currency = validate_rule(db, &rule_data).await?;
+
+    let mut rule: recurring_rule::ActiveModel = rule.into();
+    rule.record_type = Set(rule_data.record_type);
+    rule.category_id = Set(rule_data.category_id);
+    rule.account_id = Set(rule_data.account_id);
+    rule.amount = Set(rule_data.amount);
+    rule.currency = Set(currency);
+    rule.note = Set(rule_data.note);
+    rule.frequency = Set(rule_data.frequency);
+    rule.interval_days = Set(rule_data.interval_days);
+    rule.day_of_month = Set(rule_data.day_of_month);
+    rule.start_date = Set(rule_data.start_date);
+    rule.end_date = Set(rule_data.end_date);
+    rule.updated_at = Set(chrono::Utc::now().into());
+    let rule = rule.update(db).await?;
+
+    let (rule, _) = materialize_rule(db, rule, chrono::Local::now().date_naive()).await?;
+
+    Ok(rule)
+}
+
+// 已生成的记录保留，只解除与规则的关联
+pub async fn delete_recurring_rule(db: &DatabaseConnection, id: i32) -> Result<(), AppError> {
+    let txn = db.begin().await?;
+
+    ExpenseRecord::update_many()
+        .col_expr(
+            expense_record::Column::RecurringRuleId,
+            Expr::value(Option::<i32>::None),
+        )
+        .col_expr(
+            expense_record::Column::RecurringDate,
+            Expr::value(Option::<NaiveDate>::None),
+        )
+        .filter(expense_record::Column::RecurringRuleId.eq(id))
+        .exec(&txn)
+        .await?;
+
+    let result = RecurringRule::delete_by_id(id).exec(&txn).await?;
+    if result.rows_affected == 0 {
+        return Err(AppError::not_found("Recurring rule", id));
+    }
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+// 规则的第 n 次（从 0 开始）周期日期，超出日期范围时为 None
+fn nth_occurrence(rule: &recurring_rule::Model, n: u32) -> Option<NaiveDate> {
+    let start = rule.start_date;
+    match rule.frequency {
+        RecurrenceFrequency::Daily => start.checked_add_days(Days::new(n.into())),
+        RecurrenceFrequency::Weekly => start.checked_add_days(Days::new(u64::from(n) * 7)),
+        RecurrenceFrequency::EveryNDays => {
+            let interval = rule.interval_days.unwrap_or(1).max(1) as u64;
+            start.checked_add_days(Days::new(u64::from(n) * interval))
+        }
+        // 每次都从开始日期推算，2 月 29 日到了闰年仍是 29 日
+        RecurrenceFrequency::Yearly => start.checked_add_months(Months::new(n.checked_mul(12)?)),
+        RecurrenceFrequency::Monthly => {
+            let month = start.with_day(1)?.checked_add_months(Months::new(n))?;
+            let last_day = month.checked_add_months(Months::new(1))?.pred_opt()?.day();
+            let day = rule
+                .day_of_month
+                .map_or(start.day(), |day| day.clamp(1, 31) as u32);
+            month.with_day(day.min(last_day))
+        }
+    }
+}
+
+// after 之后（不含）到 until（含）之间的周期日期
+fn occurrences_between(
+    rule: &recurring_rule::Model,
+    after: Option<NaiveDate>,
+    until: NaiveDate,
+) -> Vec<NaiveDate> {
+    let mut dates = Vec::new();
+    for n in 0.. {
+        let Some(date) = nth_occurrence(rule, n) else {
+            break;
+        };
+        if date > until {
+            break;
+        }
+        // 每月指定日期早于开始日期时，开始当月的那次不生成
+        if date >= rule.start_date && after.is_none_or(|after| date > after) {
+            dates.push(date);
+        }
+    }
+    dates
+}
+
+// 生成规则在 today（含）之前尚未生成的记录，并推进 materialized_until，返回新生成的记录数
+// 依靠 (recurring_rule_id, recurring_date) 唯一索引去重，重复执行不会多生成
+async fn materialize_rule(
+    db: &DatabaseConnection,
+    rule: recurring_rule::Model,
+    today: NaiveDate,
+) -> Result<(recurring_rule::Model, u64), AppError> {
+    if rule.materialized_until.is_some_and(|until| until >= today) {
+        return Ok((rule, 0));
+    }
+
+    let until = rule.end_date.map_or(today, |end_date| end_date.min(today));
+    let dates = occurrences_between(&rule, rule.materialized_until, until);
+
+    let txn = db.begin().await?;
+
+    let created = insert_occurrences(&txn, &rule, &dates).await?;
+
+    let mut rule: recurring_rule::ActiveModel = rule.into();
+    rule.materialized_until = Set(Some(today));
+    let rule = rule.update(&txn).await?;
+
+    txn.commit().await?;
+
+    Ok((rule, created))
+}
+
+async fn insert_occurrences<C: ConnectionTrait>(
+    db: &C,
+    rule: &recurring_rule::Model,
+    dates: &[NaiveDate],
+) -> Result<u64, AppError> {
+    if dates.is_empty() {
+        return Ok(0);
+    }
+
+    let now = chrono::Utc::now();
+    let records = dates.iter().map(|&date| expense_record::ActiveModel {
+        record_type: Set(rule.record_type),
+        category_id: Set(Some(rule.category_id.clone())),
+        account_id: Set(rule.account_id),
+        amount: Set(rule.amount),
+        currency: Set(rule.currency.clone()),
+        note: Set(rule.note.clone()),
+        // 周期记录的发生时间取当天本地零点
+        occurred_at: Set(record_service::local_midnight_utc(date).into()),
+        created_at: Set(now.into()),
+        updated_at: Set(now.into()),
+        recurring_rule_id: Set(Some(rule.id)),
+        recurring_date: Set(Some(date)),
+        ..Default::default()
+    });
+
+    Ok(ExpenseRecord::insert_many(records)
+        .on_conflict(
+            OnConflict::columns([
+                expense_record::Column::RecurringRuleId,
+                expense_record::Column::RecurringDate,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?)
+}
+
+/// 为所有规则补上 today（含）之前到期的记录，返回新生成的记录数
+pub async fn materialize_due_records(
+    db: &DatabaseConnection,
+    today: NaiveDate,
+) -> Result<u64, AppError> {
+    let rules = RecurringRule::find()
+        .filter(recurring_rule::Column::StartDate.lte(today))
+        .filter(
+            Condition::any()
+                .add(recurring_rule::Column::MaterializedUntil.is_null())
+                .add(recurring_rule::Column::MaterializedUntil.lt(today)),
+        )
+        .all(db)
+        .await?;
+
+    let mut created = 0;
+    for rule in rules {
+        created += materialize_rule(db, rule, today).await?.1;
+    }
+
+    Ok(created)
+}
+
+/// 距下一次检查的时间：下一个本地零点，最长不超过 `MAX_CHECK_INTERVAL`
+pub fn next_check_delay(now: chrono::DateTime<chrono::Local>) -> Duration {
+    let next_midnight = now
+        .date_naive()
+        .succ_opt()
+        .map(record_service::local_midnight_utc)
+        .unwrap_or_else(|| now.to_utc());
+    (next_midnight - now.to_utc())
+        .to_std()
+        .unwrap_or_default()
+        .min(MAX_CHECK_INTERVAL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_connection;
+    use crate::database::test_util::date;
+
+    fn rule_data(
+        frequency: RecurrenceFrequency,
+        start_date: &str,
+        day_of_month: Option<i32>,
+    ) -> RecurringRuleData {
+        RecurringRuleData {
+            record_type: RecordType::Expense,
+            category_id: "food".into(),
+            account_id: None,
+            amount: Decimal::new(3000, 0),
+            currency: None,
+            note: None,
+            frequency,
+            interval_days: None,
+            day_of_month,
+            start_date: date(start_date),
+            end_date: None,
+        }
+    }
+
+    async fn materialized_dates(db: &DatabaseConnection, rule_id: i32) -> Vec<String> {
+        ExpenseRecord::find()
+            .filter(expense_record::Column::RecurringRuleId.eq(rule_id))
+            .order_by_asc(expense_record::Column::RecurringDate)
+            .all(db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|record| record.recurring_date.unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn monthly_rule_clamps_to_month_end() {
+        let db = test_connection().await;
+        let rule = create_recurring_rule(
+            &db,
+            rule_data(RecurrenceFrequency::Monthly, "2030-01-15", Some(31)),
+        )
+        .await
+        .unwrap();
+
+        materialize_due_records(&db, date("2030-04-30"))
+            .await
+            .unwrap();
+
+        // 小月取月末，之后的月份仍回到 31 日
+        assert_eq!(
+            materialized_dates(&db, rule.id).await,
+            ["2030-01-31", "2030-02-28", "2030-03-31", "2030-04-30"]
+        );
+    }
+
+    #[tokio::test]
+    async fn yearly_rule_on_feb_29_falls_back_in_common_years() {
+        let db = test_connection().await;
+        let rule = create_recurring_rule(
+            &db,
+            rule_data(RecurrenceFrequency::Yearly, "2028-02-29", None),
+        )
+        .await
+        .unwrap();
+
+        materialize_due_records(&db, date("2032-03-01"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            materialized_dates(&db, rule.id).await,
+            [
+                "2028-02-29",
+                "2029-02-28",
+                "2030-02-28",
+                "2031-02-28",
+                "2032-02-29"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn materializing_twice_creates_no_duplicates() {
+        let db = test_connection().await;
+        create_recurring_rule(
+            &db,
+            rule_data(RecurrenceFrequency::Weekly, "2030-01-01", None),
+        )
+        .await
+        .unwrap();
+
+        let created = materialize_due_records(&db, date("2030-01-31"))
+            .await
+            .unwrap();
+        assert_eq!(created, 5);
+        assert_eq!(
+            materialize_due_records(&db, date("2030-01-31"))
+                .await
+                .unwrap(),
+            0
+        );
+    }
+}
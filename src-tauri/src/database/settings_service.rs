@@ -0,0 +1,60 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ConnectionTrait, EntityTrait, Set};
+
+use crate::database::validation::{self, Validator};
+use crate::entities::{app_setting, AppSetting};
+use crate::error::AppError;
+
+// 已写入的默认分类版本
+pub const DEFAULT_CATEGORIES_VERSION_KEY: &str = "default_categories_version";
+// 本位币，统计结果均换算为该币种
+pub const BASE_CURRENCY_KEY: &str = "base_currency";
+pub const DEFAULT_BASE_CURRENCY: &str = "CNY";
+
+pub async fn get_setting<C: ConnectionTrait>(
+    db: &C,
+    key: &str,
+) -> Result<Option<String>, AppError> {
+    Ok(AppSetting::find_by_id(key)
+        .one(db)
+        .await?
+        .map(|setting| setting.value))
+}
+
+// 不存在则插入，存在则覆盖
+pub async fn set_setting<C: ConnectionTrait>(
+    db: &C,
+    key: &str,
+    value: &str,
+) -> Result<(), AppError> {
+    let setting = app_setting::ActiveModel {
+        key: Set(key.to_string()),
+        value: Set(value.to_string()),
+        updated_at: Set(chrono::Utc::now().into()),
+    };
+
+    AppSetting::insert(setting)
+        .on_conflict(
+            OnConflict::column(app_setting::Column::Key)
+                .update_columns([app_setting::Column::Value, app_setting::Column::UpdatedAt])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_base_currency<C: ConnectionTrait>(db: &C) -> Result<String, AppError> {
+    Ok(get_setting(db, BASE_CURRENCY_KEY)
+        .await?
+        .unwrap_or_else(|| DEFAULT_BASE_CURRENCY.to_string()))
+}
+
+pub async fn set_base_currency<C: ConnectionTrait>(db: &C, currency: &str) -> Result<(), AppError> {
+    let mut validator = Validator::new();
+    validation::check_currency(&mut validator, "currency", currency);
+    validator.finish()?;
+
+    set_setting(db, BASE_CURRENCY_KEY, currency).await
+}
@@ -0,0 +1,265 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::database::validation::{self, Validator};
+use crate::entities::{record_tag, tag, RecordTag, Tag};
+use crate::error::AppError;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagData {
+    pub name: String,
+}
+
+pub async fn get_tags(db: &DatabaseConnection) -> Result<Vec<tag::Model>, AppError> {
+    Ok(Tag::find().order_by_asc(tag::Column::Name).all(db).await?)
+}
+
+// 去掉首尾空白和空名称，按名称排序并去重
+pub(crate) fn normalize_tag_names(names: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = names
+        .iter()
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+fn check_tag_name(validator: &mut Validator, field: &str, name: &str) {
+    let length = name.trim().chars().count();
+    validator.check(
+        length > 0 && length <= validation::MAX_TAG_NAME_LENGTH,
+        field,
+        format!(
+            "Tag must be 1-{} characters",
+            validation::MAX_TAG_NAME_LENGTH
+        ),
+    );
+}
+
+/// 校验记录上的标签名称长度与数量
+pub(crate) fn check_tags(validator: &mut Validator, field: &str, names: &[String]) {
+    let names = normalize_tag_names(names);
+    validator.check(
+        names.len() <= validation::MAX_RECORD_TAGS,
+        field,
+        format!("At most {} tags", validation::MAX_RECORD_TAGS),
+    );
+    for name in &names {
+        check_tag_name(validator, field, name);
+    }
+}
+
+// 重命名为已有的名称时冲突，需要合并请先删除其中一个
+pub async fn update_tag(
+    db: &DatabaseConnection,
+    id: i32,
+    tag_data: TagData,
+) -> Result<tag::Model, AppError> {
+    let mut validator = Validator::new();
+    check_tag_name(&mut validator, "name", &tag_data.name);
+    validator.finish()?;
+
+    let tag = Tag::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::not_found("Tag", id))?;
+
+    let name = tag_data.name.trim().to_string();
+    let existing = Tag::find()
+        .filter(tag::Column::Name.eq(name.as_str()))
+        .filter(tag::Column::Id.ne(id))
+        .one(db)
+        .await?;
+    if existing.is_some() {
+        return Err(AppError::Conflict(format!("Tag {} already exists", name)));
+    }
+
+    let mut tag: tag::ActiveModel = tag.into();
+    tag.name = Set(name);
+
+    Ok(tag.update(db).await?)
+}
+
+// 删除标签不影响记录本身，只去掉记录上的该标签
+pub async fn delete_tag(db: &DatabaseConnection, id: i32) -> Result<(), AppError> {
+    let txn = db.begin().await?;
+
+    RecordTag::delete_many()
+        .filter(record_tag::Column::TagId.eq(id))
+        .exec(&txn)
+        .await?;
+
+    let result = Tag::delete_by_id(id).exec(&txn).await?;
+    if result.rows_affected == 0 {
+        return Err(AppError::not_found("Tag", id));
+    }
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// 用给定的标签替换记录原有的标签，不存在的标签自动创建
+pub(crate) async fn set_record_tags<C: ConnectionTrait>(
+    db: &C,
+    record_id: i32,
+    names: &[String],
+) -> Result<(), AppError> {
+    RecordTag::delete_many()
+        .filter(record_tag::Column::RecordId.eq(record_id))
+        .exec(db)
+        .await?;
+
+    let names = normalize_tag_names(names);
+    if names.is_empty() {
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now();
+    let tags = names.iter().map(|name| tag::ActiveModel {
+        name: Set(name.clone()),
+        created_at: Set(now.into()),
+        ..Default::default()
+    });
+    Tag::insert_many(tags)
+        .on_conflict(
+            OnConflict::column(tag::Column::Name)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    let tag_ids: Vec<i32> = Tag::find()
+        .filter(tag::Column::Name.is_in(names))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|tag| tag.id)
+        .collect();
+    let links = tag_ids.into_iter().map(|tag_id| record_tag::ActiveModel {
+        record_id: Set(record_id),
+        tag_id: Set(tag_id),
+    });
+    RecordTag::insert_many(links)
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
+
+/// 各记录的标签名称，按名称排列；没有标签的记录不在结果中
+pub(crate) async fn get_record_tags(
+    db: &DatabaseConnection,
+    record_ids: &[i32],
+) -> Result<HashMap<i32, Vec<String>>, AppError> {
+    let links = RecordTag::find()
+        .find_also_related(Tag)
+        .filter(record_tag::Column::RecordId.is_in(record_ids.iter().copied()))
+        .order_by_asc(tag::Column::Name)
+        .all(db)
+        .await?;
+
+    let mut tags: HashMap<i32, Vec<String>> = HashMap::new();
+    for (link, tag) in links {
+        if let Some(tag) = tag {
+            tags.entry(link.record_id).or_default().push(tag.name);
+        }
+    }
+
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::record_service::{self, ExpenseRecordData};
+    use crate::database::test_connection;
+    use crate::database::test_util::record_data;
+    use crate::entities::RecordType;
+    use rust_decimal::Decimal;
+
+    async fn tagged_record(db: &DatabaseConnection, tags: &[&str]) -> i32 {
+        record_service::create_record(
+            db,
+            ExpenseRecordData {
+                tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+                ..record_data(RecordType::Expense, "food", Decimal::new(10, 0), None)
+            },
+        )
+        .await
+        .unwrap()
+        .record
+        .id
+    }
+
+    fn tag_id(tags: &[tag::Model], name: &str) -> i32 {
+        tags.iter().find(|tag| tag.name == name).unwrap().id
+    }
+
+    #[tokio::test]
+    async fn renaming_to_an_existing_tag_conflicts() {
+        let db = test_connection().await;
+        tagged_record(&db, &["work", "trip"]).await;
+        let tags = get_tags(&db).await.unwrap();
+
+        let result = update_tag(
+            &db,
+            tag_id(&tags, "work"),
+            TagData {
+                name: " trip ".into(),
+            },
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+
+        let result = update_tag(
+            &db,
+            tag_id(&tags, "work"),
+            TagData {
+                name: "x".repeat(validation::MAX_TAG_NAME_LENGTH + 1),
+            },
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+
+        let renamed = update_tag(
+            &db,
+            tag_id(&tags, "work"),
+            TagData {
+                name: " office ".into(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(renamed.name, "office");
+    }
+
+    #[tokio::test]
+    async fn deleting_a_tag_keeps_its_records() {
+        let db = test_connection().await;
+        let record_id = tagged_record(&db, &["work", "trip"]).await;
+        let tags = get_tags(&db).await.unwrap();
+
+        delete_tag(&db, tag_id(&tags, "work")).await.unwrap();
+
+        let record = record_service::get_record_by_id(&db, record_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let record = record_service::with_tags(&db, vec![record]).await.unwrap();
+        assert_eq!(record[0].tags, ["trip"]);
+        assert!(matches!(
+            delete_tag(&db, tag_id(&tags, "work")).await,
+            Err(AppError::NotFound { .. })
+        ));
+    }
+}
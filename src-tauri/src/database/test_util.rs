@@ -0,0 +1,78 @@
+//! 各服务测试共用的夹具
+
+use rust_decimal::Decimal;
+use sea_orm::DatabaseConnection;
+
+use crate::database::account_service::{self, AccountData};
+use crate::database::record_service::{self, ExpenseRecordData};
+use crate::entities::{expense_record, AccountType, RecordType};
+use crate::error::AppError;
+
+pub(crate) fn time(value: &str) -> chrono::DateTime<chrono::Utc> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .unwrap()
+        .with_timezone(&chrono::Utc)
+}
+
+pub(crate) fn date(value: &str) -> chrono::NaiveDate {
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+}
+
+// 校验失败的字段名
+pub(crate) fn invalid_fields<T: std::fmt::Debug>(result: Result<T, AppError>) -> Vec<String> {
+    match result {
+        Err(AppError::Validation(fields)) => fields.into_iter().map(|field| field.field).collect(),
+        other => panic!("expected validation error, got {:?}", other),
+    }
+}
+
+// 不带账户和标签的记录，occurred_at 为空时取当前时间
+pub(crate) fn record_data(
+    record_type: RecordType,
+    category_id: &str,
+    amount: Decimal,
+    occurred_at: Option<&str>,
+) -> ExpenseRecordData {
+    ExpenseRecordData {
+        record_type,
+        category_id: category_id.into(),
+        account_id: None,
+        amount,
+        currency: None,
+        note: None,
+        occurred_at: occurred_at.map(|value| chrono::DateTime::parse_from_rfc3339(value).unwrap()),
+        tags: None,
+    }
+}
+
+// 记一笔支出
+pub(crate) async fn spend(
+    db: &DatabaseConnection,
+    category_id: &str,
+    amount: Decimal,
+    occurred_at: Option<&str>,
+) -> expense_record::Model {
+    record_service::create_record(
+        db,
+        record_data(RecordType::Expense, category_id, amount, occurred_at),
+    )
+    .await
+    .unwrap()
+    .record
+}
+
+// 新建一个期初余额为零的现金账户
+pub(crate) async fn account(db: &DatabaseConnection, name: &str, currency: &str) -> i32 {
+    account_service::create_account(
+        db,
+        AccountData {
+            name: name.into(),
+            account_type: AccountType::Cash,
+            opening_balance: Decimal::ZERO,
+            currency: currency.into(),
+        },
+    )
+    .await
+    .unwrap()
+    .id
+}
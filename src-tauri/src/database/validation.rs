@@ -0,0 +1,107 @@
+use rust_decimal::Decimal;
+
+use crate::error::{AppError, FieldError};
+
+// 备注最大字符数
+pub const MAX_NOTE_LENGTH: usize = 200;
+// 分类名称最大字符数
+pub const MAX_CATEGORY_NAME_LENGTH: usize = 20;
+// 账户名称最大字符数
+pub const MAX_ACCOUNT_NAME_LENGTH: usize = 30;
+// 标签名称最大字符数
+pub const MAX_TAG_NAME_LENGTH: usize = 20;
+// 单条记录最多的标签数
+pub const MAX_RECORD_TAGS: usize = 10;
+// 搜索关键词最大字符数
+pub const MAX_SEARCH_QUERY_LENGTH: usize = 100;
+// 分类 ID 与图标名称最大长度
+pub const MAX_IDENTIFIER_LENGTH: usize = 64;
+
+// 前端可用的颜色名，也接受 #RRGGBB 形式
+const NAMED_COLORS: &[&str] = &[
+    "red", "orange", "yellow", "green", "cyan", "blue", "purple", "pink", "brown", "grey", "gray",
+];
+
+/// 收集字段级错误，最后统一返回
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        });
+    }
+
+    pub fn check(&mut self, ok: bool, field: &str, message: impl Into<String>) {
+        if !ok {
+            self.add(field, message);
+        }
+    }
+
+    pub fn finish(self) -> Result<(), AppError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(self.errors))
+        }
+    }
+}
+
+/// 金额必须为正数且最多两位小数
+pub fn check_amount(validator: &mut Validator, field: &str, amount: Decimal) {
+    validator.check(
+        amount > Decimal::ZERO,
+        field,
+        "Amount must be greater than 0",
+    );
+    validator.check(
+        amount.normalize().scale() <= 2,
+        field,
+        "Amount must have at most 2 decimal places",
+    );
+}
+
+/// 小写字母、数字与下划线组成的标识符
+pub fn check_identifier(validator: &mut Validator, field: &str, value: &str) {
+    let valid = !value.is_empty()
+        && value.len() <= MAX_IDENTIFIER_LENGTH
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    validator.check(
+        valid,
+        field,
+        format!(
+            "Must be 1-{} characters of a-z, 0-9 or _",
+            MAX_IDENTIFIER_LENGTH
+        ),
+    );
+}
+
+pub fn check_color(validator: &mut Validator, field: &str, value: &str) {
+    let is_hex = value.len() == 7
+        && value.starts_with('#')
+        && value[1..].chars().all(|c| c.is_ascii_hexdigit());
+    validator.check(
+        is_hex || NAMED_COLORS.contains(&value),
+        field,
+        "Color must be a named color or #RRGGBB",
+    );
+}
+
+/// ISO 4217 货币代码，三个大写字母
+pub fn check_currency(validator: &mut Validator, field: &str, value: &str) {
+    validator.check(
+        value.len() == 3 && value.chars().all(|c| c.is_ascii_uppercase()),
+        field,
+        "Currency must be a 3-letter ISO 4217 code",
+    );
+}
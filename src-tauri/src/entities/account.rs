@@ -0,0 +1,32 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::sea_orm_active_enums::AccountType;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "accounts")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+    pub name: String,
+    pub account_type: AccountType,
+    pub opening_balance: Decimal, // 开户余额，信用卡欠款为负数
+    pub currency: String,         // ISO 4217 货币代码，如 CNY
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::expense_record::Entity")]
+    ExpenseRecord,
+}
+
+impl Related<super::expense_record::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExpenseRecord.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,62 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Serialize};
+
+use super::sea_orm_active_enums::BudgetPeriod;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "budgets")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+    pub period: BudgetPeriod,
+    pub category_id: Option<String>, // 为空表示全部支出，否则包含其子分类
+    pub amount: Decimal,             // 本位币，信封分配过的月份以分配额为准
+    pub rollover: bool,              // 结余或超支结转到下一周期，仅月、年预算支持
+    #[sea_orm(column_type = "Json")]
+    pub alert_thresholds: AlertThresholds, // 使用比例达到这些百分比时提醒
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::category::Entity",
+        from = "Column::CategoryId",
+        to = "super::category::Column::CategoryId",
+        on_update = "Cascade",
+        on_delete = "Restrict"
+    )]
+    Category,
+    #[sea_orm(has_many = "super::budget_allocation::Entity")]
+    BudgetAllocation,
+    #[sea_orm(has_many = "super::budget_alert::Entity")]
+    BudgetAlert,
+}
+
+impl Related<super::category::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Category.def()
+    }
+}
+
+impl Related<super::budget_allocation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BudgetAllocation.def()
+    }
+}
+
+impl Related<super::budget_alert::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BudgetAlert.def()
+    }
+}
+
+/// 预算提醒阈值（百分比），从小到大排列，以 JSON 数组保存
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(transparent)]
+pub struct AlertThresholds(pub Vec<i32>);
+
+impl ActiveModelBehavior for ActiveModel {}
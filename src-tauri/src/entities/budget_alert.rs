@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "budget_alerts")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+    pub budget_id: i32,
+    pub start_date: Date,      // 触发提醒的周期首日
+    pub threshold: i32,        // 达到的阈值百分比
+    pub percent_used: Decimal, // 触发时的使用比例
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::budget::Entity",
+        from = "Column::BudgetId",
+        to = "super::budget::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Budget,
+}
+
+impl Related<super::budget::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Budget.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
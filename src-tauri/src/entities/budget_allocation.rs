@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "budget_allocations")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+    pub budget_id: i32,
+    pub start_date: Date,       // 周期首日（本地日期）
+    pub amount: Decimal,        // 该周期的额度
+    pub rollover: bool,         // 该周期的结余是否结转到下一周期
+    pub spent: Option<Decimal>, // 周期结束时的支出，结束前为空
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::budget::Entity",
+        from = "Column::BudgetId",
+        to = "super::budget::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Budget,
+}
+
+impl Related<super::budget::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Budget.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,6 +1,8 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use super::sea_orm_active_enums::CategoryType;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "categories")]
 pub struct Model {
@@ -9,9 +11,13 @@ pub struct Model {
     pub id: i32,
     pub category_id: String,
     pub name: String,
+    pub name_key: Option<String>, // 默认分类的翻译 key，用户新建或改名后为空
     pub icon: String,
     pub color: String,
-    pub category_type: String, // "expense" or "income"
+    pub category_type: CategoryType,
+    pub parent_id: Option<String>, // 父分类的 category_id，顶级分类为空
+    pub archived: bool,            // 归档后不在选择列表中显示，历史记录仍保留
+    pub sort_order: i32,           // 用户自定义顺序，数值越小越靠前
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
 }
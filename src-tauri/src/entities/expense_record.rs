@@ -1,18 +1,28 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use super::sea_orm_active_enums::RecordType;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "expense_records")]
 pub struct Model {
     #[sea_orm(primary_key)]
     #[serde(skip_deserializing)]
     pub id: i32,
-    pub record_type: String, // "expense" or "income"
-    pub category_id: String,
+    pub record_type: RecordType,
+    pub category_id: Option<String>, // 转账记录没有分类
+    pub account_id: Option<i32>,     // 所属账户，历史记录可为空；转账时为转出账户
+    pub to_account_id: Option<i32>,  // 转账的转入账户
     pub amount: Decimal,
+    pub currency: String, // ISO 4217 货币代码，统计时按记录日期的汇率换算为本位币
+    pub fee: Option<Decimal>, // 转账手续费，从转出账户扣除
     pub note: Option<String>,
+    pub occurred_at: DateTimeWithTimeZone, // 实际发生时间，统计与排序均以此为准
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub deleted_at: Option<DateTimeWithTimeZone>, // 移入回收站的时间
+    pub recurring_rule_id: Option<i32>,           // 生成该记录的周期规则
+    pub recurring_date: Option<Date>,             // 对应的周期日期
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -22,9 +32,18 @@ pub enum Relation {
         from = "Column::CategoryId",
         to = "super::category::Column::CategoryId",
         on_update = "Cascade",
-        on_delete = "Cascade"
+        on_delete = "Restrict"
     )]
     Category,
+    #[sea_orm(
+        belongs_to = "super::account::Entity",
+        from = "Column::AccountId",
+        to = "super::account::Column::Id",
+        on_delete = "Restrict"
+    )]
+    Account,
+    #[sea_orm(has_many = "super::record_tag::Entity")]
+    RecordTag,
 }
 
 impl Related<super::category::Entity> for Entity {
@@ -33,4 +52,16 @@ impl Related<super::category::Entity> for Entity {
     }
 }
 
+impl Related<super::account::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Account.def()
+    }
+}
+
+impl Related<super::record_tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecordTag.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
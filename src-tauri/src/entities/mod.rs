@@ -1,5 +1,27 @@
+pub mod account;
+pub mod app_setting;
+pub mod budget;
+pub mod budget_alert;
+pub mod budget_allocation;
 pub mod category;
+pub mod exchange_rate;
 pub mod expense_record;
+pub mod record_tag;
+pub mod recurring_rule;
+pub mod sea_orm_active_enums;
+pub mod tag;
 
+pub use account::Entity as Account;
+pub use app_setting::Entity as AppSetting;
+pub use budget::Entity as Budget;
+pub use budget_alert::Entity as BudgetAlert;
+pub use budget_allocation::Entity as BudgetAllocation;
 pub use category::Entity as Category;
+pub use exchange_rate::Entity as ExchangeRate;
 pub use expense_record::Entity as ExpenseRecord;
+pub use record_tag::Entity as RecordTag;
+pub use recurring_rule::Entity as RecurringRule;
+pub use sea_orm_active_enums::{
+    AccountType, BudgetPeriod, CategoryType, RecordType, RecurrenceFrequency,
+};
+pub use tag::Entity as Tag;
@@ -0,0 +1,45 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "record_tags")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub record_id: i32,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub tag_id: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::expense_record::Entity",
+        from = "Column::RecordId",
+        to = "super::expense_record::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    ExpenseRecord,
+    #[sea_orm(
+        belongs_to = "super::tag::Entity",
+        from = "Column::TagId",
+        to = "super::tag::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Tag,
+}
+
+impl Related<super::expense_record::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExpenseRecord.def()
+    }
+}
+
+impl Related<super::tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Tag.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
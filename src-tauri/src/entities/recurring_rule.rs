@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::sea_orm_active_enums::{RecordType, RecurrenceFrequency};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "recurring_rules")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+    pub record_type: RecordType,
+    pub category_id: String,
+    pub account_id: Option<i32>,
+    pub amount: Decimal,
+    pub currency: String,
+    pub note: Option<String>,
+    pub frequency: RecurrenceFrequency,
+    pub interval_days: Option<i32>, // every_n_days 的间隔天数
+    pub day_of_month: Option<i32>,  // monthly 的日期
+    pub start_date: Date,
+    pub end_date: Option<Date>, // 最后一个可生成的日期，为空表示一直重复
+    pub materialized_until: Option<Date>, // 已生成到该日期（含）
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::category::Entity",
+        from = "Column::CategoryId",
+        to = "super::category::Column::CategoryId",
+        on_update = "Cascade",
+        on_delete = "Restrict"
+    )]
+    Category,
+}
+
+impl Related<super::category::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Category.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
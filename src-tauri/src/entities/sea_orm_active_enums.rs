@@ -0,0 +1,88 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "lowercase")]
+pub enum RecordType {
+    #[sea_orm(string_value = "expense")]
+    Expense,
+    #[sea_orm(string_value = "income")]
+    Income,
+    /// 账户间转账，不计入收支统计
+    #[sea_orm(string_value = "transfer")]
+    Transfer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "lowercase")]
+pub enum CategoryType {
+    #[sea_orm(string_value = "expense")]
+    Expense,
+    #[sea_orm(string_value = "income")]
+    Income,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum AccountType {
+    #[sea_orm(string_value = "cash")]
+    Cash,
+    #[sea_orm(string_value = "bank_card")]
+    BankCard,
+    #[sea_orm(string_value = "credit_card")]
+    CreditCard,
+    #[sea_orm(string_value = "alipay")]
+    Alipay,
+    #[sea_orm(string_value = "wechat_pay")]
+    WechatPay,
+}
+
+impl CategoryType {
+    /// 该类型分类可用于哪种记录
+    pub fn accepts(&self, record_type: RecordType) -> bool {
+        matches!(
+            (self, record_type),
+            (CategoryType::Expense, RecordType::Expense)
+                | (CategoryType::Income, RecordType::Income)
+        )
+    }
+}
+
+/// 周期记录的重复方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum RecurrenceFrequency {
+    #[sea_orm(string_value = "daily")]
+    Daily,
+    /// 每周与开始日期同一星期几
+    #[sea_orm(string_value = "weekly")]
+    Weekly,
+    /// 每月 day_of_month 号，缺省为开始日期的日；当月没有该日时取月末
+    #[sea_orm(string_value = "monthly")]
+    Monthly,
+    /// 每年与开始日期同月同日，2 月 29 日在平年取 28 日
+    #[sea_orm(string_value = "yearly")]
+    Yearly,
+    /// 每隔 interval_days 天
+    #[sea_orm(string_value = "every_n_days")]
+    EveryNDays,
+}
+
+/// 预算周期，周预算的起始日由查询时的 week_start 决定
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetPeriod {
+    #[sea_orm(string_value = "weekly")]
+    Weekly,
+    #[sea_orm(string_value = "monthly")]
+    Monthly,
+    #[sea_orm(string_value = "yearly")]
+    Yearly,
+}
@@ -0,0 +1,126 @@
+use sea_orm::{DbErr, SqlErr};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// 单个字段的校验错误
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// 命令层统一的错误类型，序列化为带稳定 `code` 的 JSON 对象：
+/// `{ "code": "NOT_FOUND", "message": "...", "fields": [...] }`
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("{entity} not found: {id}")]
+    NotFound { entity: &'static str, id: String },
+    #[error("Validation failed: {}", format_fields(.0))]
+    Validation(Vec<FieldError>),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("Database error: {0}")]
+    Database(String),
+    #[error("Database is not ready: {0}")]
+    DatabaseNotReady(String),
+}
+
+impl AppError {
+    pub fn not_found(entity: &'static str, id: impl ToString) -> Self {
+        AppError::NotFound {
+            entity,
+            id: id.to_string(),
+        }
+    }
+
+    /// 单个字段校验失败
+    pub fn validation(field: impl Into<String>, message: impl Into<String>) -> Self {
+        AppError::Validation(vec![FieldError {
+            field: field.into(),
+            message: message.into(),
+        }])
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound { .. } => "NOT_FOUND",
+            AppError::Validation(_) => "VALIDATION",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::Database(_) => "DATABASE",
+            AppError::DatabaseNotReady(_) => "DATABASE_NOT_READY",
+        }
+    }
+}
+
+fn format_fields(fields: &[FieldError]) -> String {
+    fields
+        .iter()
+        .map(|f| format!("{}: {}", f.field, f.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl From<DbErr> for AppError {
+    fn from(err: DbErr) -> Self {
+        // 找不到记录时由各服务在查询处返回带实体名的 NotFound，这里不再猜测实体
+        match err.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(msg)) => AppError::Conflict(msg),
+            Some(SqlErr::ForeignKeyConstraintViolation(msg)) => AppError::Conflict(msg),
+            _ => AppError::Database(err.to_string()),
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let fields = match self {
+            AppError::Validation(fields) => Some(fields),
+            _ => None,
+        };
+
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("fields", &fields)?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_message_and_fields() {
+        let error = AppError::validation("amount", "Amount must be greater than 0");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "VALIDATION",
+                "message": "Validation failed: amount: Amount must be greater than 0",
+                "fields": [{ "field": "amount", "message": "Amount must be greater than 0" }],
+            })
+        );
+
+        let error = AppError::not_found("Record", 7);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "NOT_FOUND",
+                "message": "Record not found: 7",
+                "fields": null,
+            })
+        );
+    }
+
+    #[test]
+    fn missing_rows_from_the_database_are_not_guessed_as_records() {
+        let error = AppError::from(DbErr::RecordNotFound(
+            "None of the records are updated".to_string(),
+        ));
+        assert_eq!(error.code(), "DATABASE");
+        assert!(error
+            .to_string()
+            .contains("None of the records are updated"));
+    }
+}
@@ -1,6 +1,7 @@
+use sea_orm::DatabaseConnection;
 use std::path::PathBuf;
 use tauri::plugin::TauriPlugin;
-use tauri::{Manager, Runtime};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 use tauri_plugin_log::fern::colors::Color;
 use tauri_plugin_log::fern::colors::ColoredLevelConfig;
 use tauri_plugin_log::{Target, TargetKind, WEBVIEW_TARGET};
@@ -8,17 +9,23 @@ use tauri_plugin_log::{Target, TargetKind, WEBVIEW_TARGET};
 mod commands;
 mod database;
 mod entities;
+mod error;
 mod migration;
 
 #[cfg(desktop)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(build_log_plugin())
         .setup(|app| {
+            // 数据库句柄在启动时即注册，命令会等待其初始化完成
+            app.manage(database::DbHandle::new());
+
             // 初始化数据库
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
+                let handle = app_handle.state::<database::DbHandle>();
                 match database::establish_connection().await {
                     Ok(db) => {
                         // 初始化默认分类数据
@@ -30,11 +37,41 @@ pub fn run() {
                             log::info!("Database initialized successfully");
                         }
 
-                        // 将数据库连接保存到应用状态
-                        app_handle.manage(db);
+                        // 清理回收站中超期的记录
+                        match database::record_service::purge_expired_trash(
+                            &db,
+                            database::record_service::TRASH_RETENTION_DAYS,
+                        )
+                        .await
+                        {
+                            Ok(count) if count > 0 => {
+                                log::info!("Purged {} expired records from trash", count)
+                            }
+                            Ok(_) => {}
+                            Err(e) => log::error!("Failed to purge expired trash: {}", e),
+                        }
+
+                        // 结束已过去的预算周期，之后由定时任务每天检查
+                        close_budget_periods(&db, chrono::Local::now().date_naive()).await;
+
+                        tauri::async_runtime::spawn(run_recurring_scheduler(
+                            app_handle.clone(),
+                            db.clone(),
+                        ));
+
+                        handle.set_ready(db);
+                        if let Err(e) = app_handle.emit("db-ready", ()) {
+                            log::error!("Failed to emit db-ready event: {}", e);
+                        }
                     }
                     Err(e) => {
                         log::error!("Failed to establish database connection: {}", e);
+
+                        let message = e.to_string();
+                        handle.set_failed(message.clone());
+                        if let Err(e) = app_handle.emit("db-failed", message) {
+                            log::error!("Failed to emit db-failed event: {}", e);
+                        }
                     }
                 }
             });
@@ -59,21 +96,109 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_categories,
+            commands::get_category_tree,
             commands::get_category_by_id,
             commands::create_category,
             commands::update_category,
+            commands::move_category,
+            commands::reorder_categories,
             commands::delete_category,
+            commands::set_category_archived,
+            commands::merge_categories,
+            commands::get_accounts,
+            commands::get_account_by_id,
+            commands::create_account,
+            commands::update_account,
+            commands::delete_account,
+            commands::get_account_balances,
+            commands::get_base_currency,
+            commands::set_base_currency,
+            commands::get_exchange_rates,
+            commands::set_exchange_rate,
+            commands::delete_exchange_rate,
+            commands::import_exchange_rates,
+            commands::get_recurring_rules,
+            commands::create_recurring_rule,
+            commands::update_recurring_rule,
+            commands::delete_recurring_rule,
+            commands::get_budgets,
+            commands::create_budget,
+            commands::update_budget,
+            commands::delete_budget,
+            commands::get_budget_status,
+            commands::set_budget_allocations,
+            commands::get_envelope_summary,
+            commands::get_tags,
+            commands::update_tag,
+            commands::delete_tag,
             commands::get_records,
+            commands::search_records,
             commands::get_record_by_id,
             commands::create_record,
             commands::update_record,
+            commands::create_transfer,
+            commands::update_transfer,
             commands::delete_record,
-            commands::get_statistics
+            commands::get_trash,
+            commands::restore_record,
+            commands::purge_record,
+            commands::empty_trash,
+            commands::get_statistics,
+            commands::get_category_breakdown,
+            commands::get_tag_breakdown,
+            commands::get_time_series
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
+// 启动时及每天零点生成到期的周期记录，错过的周期一并补上；失败时下次检查重试
+// 每天同时结束已过去的预算周期
+async fn run_recurring_scheduler<R: Runtime>(app_handle: AppHandle<R>, db: DatabaseConnection) {
+    use database::recurring_service;
+
+    let mut last_run = None;
+    let mut last_closed = None;
+    loop {
+        let today = chrono::Local::now().date_naive();
+        if last_closed != Some(today) && close_budget_periods(&db, today).await {
+            last_closed = Some(today);
+        }
+        if last_run != Some(today) {
+            match recurring_service::materialize_due_records(&db, today).await {
+                Ok(count) => {
+                    last_run = Some(today);
+                    if count > 0 {
+                        log::info!("Created {} records from recurring rules", count);
+                        if let Err(e) = app_handle.emit("recurring-records-created", count) {
+                            log::error!("Failed to emit recurring-records-created event: {}", e);
+                        }
+                    }
+                }
+                Err(e) => log::error!("Failed to materialize recurring records: {}", e),
+            }
+        }
+
+        tokio::time::sleep(recurring_service::next_check_delay(chrono::Local::now())).await;
+    }
+}
+
+// 为 today 之前结束的预算周期写入快照，查询预算执行情况时按快照计算结转；失败时返回 false
+async fn close_budget_periods(db: &DatabaseConnection, today: chrono::NaiveDate) -> bool {
+    match database::budget_service::close_budget_periods(db, today).await {
+        Ok(count) => {
+            if count > 0 {
+                log::info!("Closed {} budget periods", count);
+            }
+            true
+        }
+        Err(e) => {
+            log::error!("Failed to close budget periods: {}", e);
+            false
+        }
+    }
+}
+
 fn build_log_plugin<R: Runtime>() -> TauriPlugin<R> {
     // 获取当前工作目录并创建 logs 子目录
     let mut log_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 不允许用非常量默认值新增 NOT NULL 列，先给占位值再回填
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExpenseRecords::Table)
+                    .add_column(
+                        ColumnDef::new(ExpenseRecords::OccurredAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default("1970-01-01 00:00:00+00:00"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 历史记录的发生时间即创建时间
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(ExpenseRecords::Table)
+                    .value(
+                        ExpenseRecords::OccurredAt,
+                        Expr::col(ExpenseRecords::CreatedAt),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_expense_records_occurred_at")
+                    .table(ExpenseRecords::Table)
+                    .col(ExpenseRecords::OccurredAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_expense_records_occurred_at")
+                    .table(ExpenseRecords::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExpenseRecords::Table)
+                    .drop_column(ExpenseRecords::OccurredAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ExpenseRecords {
+    Table,
+    CreatedAt,
+    OccurredAt,
+}
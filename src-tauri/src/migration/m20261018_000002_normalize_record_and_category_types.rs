@@ -0,0 +1,104 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 统一为小写并去掉首尾空白，修复 "Expense" 之类的脏数据
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Categories::Table)
+                    .value(
+                        Categories::CategoryType,
+                        Func::lower(
+                            Func::cust(Alias::new("TRIM")).arg(Expr::col(Categories::CategoryType)),
+                        ),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(ExpenseRecords::Table)
+                    .value(
+                        ExpenseRecords::RecordType,
+                        Func::lower(
+                            Func::cust(Alias::new("TRIM"))
+                                .arg(Expr::col(ExpenseRecords::RecordType)),
+                        ),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 仍无法识别的分类按支出处理
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Categories::Table)
+                    .value(Categories::CategoryType, "expense")
+                    .and_where(Expr::col(Categories::CategoryType).is_not_in(["expense", "income"]))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 仍无法识别的记录沿用其所属分类的类型，分类已不存在时按支出处理
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(ExpenseRecords::Table)
+                    .value(
+                        ExpenseRecords::RecordType,
+                        Func::coalesce([
+                            SimpleExpr::SubQuery(
+                                None,
+                                Box::new(
+                                    Query::select()
+                                        .column(Categories::CategoryType)
+                                        .from(Categories::Table)
+                                        .and_where(
+                                            Expr::col((Categories::Table, Categories::CategoryId))
+                                                .equals((
+                                                    ExpenseRecords::Table,
+                                                    ExpenseRecords::CategoryId,
+                                                )),
+                                        )
+                                        .to_owned()
+                                        .into_sub_query_statement(),
+                                ),
+                            ),
+                            Expr::val("expense").into(),
+                        ]),
+                    )
+                    .and_where(
+                        Expr::col(ExpenseRecords::RecordType).is_not_in(["expense", "income"]),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // 数据规范化无法撤销
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Categories {
+    Table,
+    CategoryId,
+    CategoryType,
+}
+
+#[derive(Iden)]
+enum ExpenseRecords {
+    Table,
+    RecordType,
+    CategoryId,
+}
@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 非空表示记录已移入回收站
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExpenseRecords::Table)
+                    .add_column(
+                        ColumnDef::new(ExpenseRecords::DeletedAt).timestamp_with_time_zone(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_expense_records_deleted_at")
+                    .table(ExpenseRecords::Table)
+                    .col(ExpenseRecords::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_expense_records_deleted_at")
+                    .table(ExpenseRecords::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExpenseRecords::Table)
+                    .drop_column(ExpenseRecords::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ExpenseRecords {
+    Table,
+    DeletedAt,
+}
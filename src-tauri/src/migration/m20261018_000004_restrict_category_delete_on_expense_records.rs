@@ -0,0 +1,163 @@
+use sea_orm::TransactionTrait;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// SQLite 无法修改已有外键，只能重建 expense_records 表
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild_expense_records(manager, ForeignKeyAction::Restrict).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild_expense_records(manager, ForeignKeyAction::Cascade).await
+    }
+}
+
+async fn rebuild_expense_records(
+    manager: &SchemaManager<'_>,
+    on_delete: ForeignKeyAction,
+) -> Result<(), DbErr> {
+    let txn = manager.get_connection().begin().await?;
+    let manager = SchemaManager::new(&txn);
+
+    manager
+        .create_table(
+            Table::create()
+                .table(ExpenseRecordsNew::Table)
+                .col(
+                    ColumnDef::new(ExpenseRecords::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(ExpenseRecords::RecordType)
+                        .string()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ExpenseRecords::CategoryId)
+                        .string()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(ExpenseRecords::Amount).decimal().not_null())
+                .col(ColumnDef::new(ExpenseRecords::Note).string())
+                .col(
+                    ColumnDef::new(ExpenseRecords::OccurredAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ExpenseRecords::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ExpenseRecords::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(ExpenseRecords::DeletedAt).timestamp_with_time_zone())
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_expense_records_category")
+                        .from(ExpenseRecordsNew::Table, ExpenseRecords::CategoryId)
+                        .to(Categories::Table, Categories::CategoryId)
+                        .on_delete(on_delete)
+                        .on_update(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    let columns = [
+        ExpenseRecords::Id,
+        ExpenseRecords::RecordType,
+        ExpenseRecords::CategoryId,
+        ExpenseRecords::Amount,
+        ExpenseRecords::Note,
+        ExpenseRecords::OccurredAt,
+        ExpenseRecords::CreatedAt,
+        ExpenseRecords::UpdatedAt,
+        ExpenseRecords::DeletedAt,
+    ];
+    manager
+        .exec_stmt(
+            Query::insert()
+                .into_table(ExpenseRecordsNew::Table)
+                .columns(columns.clone())
+                .select_from(
+                    Query::select()
+                        .columns(columns)
+                        .from(ExpenseRecords::Table)
+                        .to_owned(),
+                )
+                .map_err(|e| DbErr::Migration(e.to_string()))?
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .drop_table(Table::drop().table(ExpenseRecords::Table).to_owned())
+        .await?;
+
+    manager
+        .rename_table(
+            Table::rename()
+                .table(ExpenseRecordsNew::Table, ExpenseRecords::Table)
+                .to_owned(),
+        )
+        .await?;
+
+    // 索引随旧表一起删除，需要重建
+    manager
+        .create_index(
+            Index::create()
+                .name("idx_expense_records_occurred_at")
+                .table(ExpenseRecords::Table)
+                .col(ExpenseRecords::OccurredAt)
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx_expense_records_deleted_at")
+                .table(ExpenseRecords::Table)
+                .col(ExpenseRecords::DeletedAt)
+                .to_owned(),
+        )
+        .await?;
+
+    txn.commit().await
+}
+
+#[derive(Iden, Clone)]
+enum ExpenseRecords {
+    Table,
+    Id,
+    RecordType,
+    CategoryId,
+    Amount,
+    Note,
+    OccurredAt,
+    CreatedAt,
+    UpdatedAt,
+    DeletedAt,
+}
+
+#[derive(Iden)]
+enum ExpenseRecordsNew {
+    Table,
+}
+
+#[derive(Iden)]
+enum Categories {
+    Table,
+    CategoryId,
+}
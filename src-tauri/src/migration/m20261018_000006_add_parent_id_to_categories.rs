@@ -0,0 +1,55 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 父分类的 category_id，为空表示顶级分类
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Categories::Table)
+                    .add_column(ColumnDef::new(Categories::ParentId).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_categories_parent_id")
+                    .table(Categories::Table)
+                    .col(Categories::ParentId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_categories_parent_id")
+                    .table(Categories::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Categories::Table)
+                    .drop_column(Categories::ParentId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Categories {
+    Table,
+    ParentId,
+}
@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 用户自定义的分类顺序，数值越小越靠前
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Categories::Table)
+                    .add_column(
+                        ColumnDef::new(Categories::SortOrder)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 已有分类保持原来的插入顺序
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Categories::Table)
+                    .value(Categories::SortOrder, Expr::col(Categories::Id))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Categories::Table)
+                    .drop_column(Categories::SortOrder)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Categories {
+    Table,
+    Id,
+    SortOrder,
+}
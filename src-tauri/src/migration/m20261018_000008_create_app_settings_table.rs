@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 应用级键值设置，如默认分类版本
+        manager
+            .create_table(
+                Table::create()
+                    .table(AppSettings::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AppSettings::Key)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AppSettings::Value).string().not_null())
+                    .col(
+                        ColumnDef::new(AppSettings::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AppSettings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum AppSettings {
+    Table,
+    Key,
+    Value,
+    UpdatedAt,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 记录所属账户，历史记录为空
+        // SQLite 无法删除带外键的列，账户是否存在由 record_service 校验
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExpenseRecords::Table)
+                    .add_column(ColumnDef::new(ExpenseRecords::AccountId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_expense_records_account_id")
+                    .table(ExpenseRecords::Table)
+                    .col(ExpenseRecords::AccountId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_expense_records_account_id")
+                    .table(ExpenseRecords::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExpenseRecords::Table)
+                    .drop_column(ExpenseRecords::AccountId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ExpenseRecords {
+    Table,
+    AccountId,
+}
@@ -0,0 +1,194 @@
+use sea_orm::TransactionTrait;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 转账记录没有分类，需要把 category_id 改为可空，SQLite 只能重建 expense_records 表
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild_expense_records(manager, true).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild_expense_records(manager, false).await
+    }
+}
+
+// with_transfers 为 true 时新增 to_account_id、fee 列并允许 category_id 为空；
+// 为 false 时恢复原表结构，转账记录会被删除
+async fn rebuild_expense_records(
+    manager: &SchemaManager<'_>,
+    with_transfers: bool,
+) -> Result<(), DbErr> {
+    let txn = manager.get_connection().begin().await?;
+    let manager = SchemaManager::new(&txn);
+
+    let mut category_id = ColumnDef::new(ExpenseRecords::CategoryId);
+    category_id.string();
+    if !with_transfers {
+        category_id.not_null();
+    }
+
+    let mut table = Table::create();
+    table
+        .table(ExpenseRecordsNew::Table)
+        .col(
+            ColumnDef::new(ExpenseRecords::Id)
+                .integer()
+                .not_null()
+                .auto_increment()
+                .primary_key(),
+        )
+        .col(
+            ColumnDef::new(ExpenseRecords::RecordType)
+                .string()
+                .not_null(),
+        )
+        .col(category_id)
+        .col(ColumnDef::new(ExpenseRecords::AccountId).integer());
+    if with_transfers {
+        table.col(ColumnDef::new(ExpenseRecords::ToAccountId).integer());
+    }
+    table.col(ColumnDef::new(ExpenseRecords::Amount).decimal().not_null());
+    if with_transfers {
+        table.col(ColumnDef::new(ExpenseRecords::Fee).decimal());
+    }
+    table
+        .col(ColumnDef::new(ExpenseRecords::Note).string())
+        .col(
+            ColumnDef::new(ExpenseRecords::OccurredAt)
+                .timestamp_with_time_zone()
+                .not_null(),
+        )
+        .col(
+            ColumnDef::new(ExpenseRecords::CreatedAt)
+                .timestamp_with_time_zone()
+                .not_null(),
+        )
+        .col(
+            ColumnDef::new(ExpenseRecords::UpdatedAt)
+                .timestamp_with_time_zone()
+                .not_null(),
+        )
+        .col(ColumnDef::new(ExpenseRecords::DeletedAt).timestamp_with_time_zone())
+        .foreign_key(
+            ForeignKey::create()
+                .name("fk_expense_records_category")
+                .from(ExpenseRecordsNew::Table, ExpenseRecords::CategoryId)
+                .to(Categories::Table, Categories::CategoryId)
+                .on_delete(ForeignKeyAction::Restrict)
+                .on_update(ForeignKeyAction::Cascade),
+        );
+    manager.create_table(table).await?;
+
+    // 新增的列不在旧表中，只复制两边都有的列
+    let columns = vec![
+        ExpenseRecords::Id,
+        ExpenseRecords::RecordType,
+        ExpenseRecords::CategoryId,
+        ExpenseRecords::AccountId,
+        ExpenseRecords::Amount,
+        ExpenseRecords::Note,
+        ExpenseRecords::OccurredAt,
+        ExpenseRecords::CreatedAt,
+        ExpenseRecords::UpdatedAt,
+        ExpenseRecords::DeletedAt,
+    ];
+    if !with_transfers {
+        // 旧表结构无法保存转账记录
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(ExpenseRecords::Table)
+                    .and_where(Expr::col(ExpenseRecords::RecordType).eq("transfer"))
+                    .to_owned(),
+            )
+            .await?;
+    }
+    manager
+        .exec_stmt(
+            Query::insert()
+                .into_table(ExpenseRecordsNew::Table)
+                .columns(columns.clone())
+                .select_from(
+                    Query::select()
+                        .columns(columns)
+                        .from(ExpenseRecords::Table)
+                        .to_owned(),
+                )
+                .map_err(|e| DbErr::Migration(e.to_string()))?
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .drop_table(Table::drop().table(ExpenseRecords::Table).to_owned())
+        .await?;
+
+    manager
+        .rename_table(
+            Table::rename()
+                .table(ExpenseRecordsNew::Table, ExpenseRecords::Table)
+                .to_owned(),
+        )
+        .await?;
+
+    // 索引随旧表一起删除，需要重建
+    let mut indexes = vec![
+        (
+            "idx_expense_records_occurred_at",
+            ExpenseRecords::OccurredAt,
+        ),
+        ("idx_expense_records_deleted_at", ExpenseRecords::DeletedAt),
+        ("idx_expense_records_account_id", ExpenseRecords::AccountId),
+    ];
+    if with_transfers {
+        indexes.push((
+            "idx_expense_records_to_account_id",
+            ExpenseRecords::ToAccountId,
+        ));
+    }
+    for (name, column) in indexes {
+        manager
+            .create_index(
+                Index::create()
+                    .name(name)
+                    .table(ExpenseRecords::Table)
+                    .col(column)
+                    .to_owned(),
+            )
+            .await?;
+    }
+
+    txn.commit().await
+}
+
+#[derive(Iden, Clone)]
+enum ExpenseRecords {
+    Table,
+    Id,
+    RecordType,
+    CategoryId,
+    AccountId,
+    ToAccountId,
+    Amount,
+    Fee,
+    Note,
+    OccurredAt,
+    CreatedAt,
+    UpdatedAt,
+    DeletedAt,
+}
+
+#[derive(Iden)]
+enum ExpenseRecordsNew {
+    Table,
+}
+
+#[derive(Iden)]
+enum Categories {
+    Table,
+    CategoryId,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 记录金额的币种，历史记录均为人民币
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExpenseRecords::Table)
+                    .add_column(
+                        ColumnDef::new(ExpenseRecords::Currency)
+                            .string()
+                            .not_null()
+                            .default("CNY"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExpenseRecords::Table)
+                    .drop_column(ExpenseRecords::Currency)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ExpenseRecords {
+    Table,
+    Currency,
+}
@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1 单位 currency 在 rate_date 当天可兑换 rate 单位 quote_currency
+        manager
+            .create_table(
+                Table::create()
+                    .table(ExchangeRates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ExchangeRates::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ExchangeRates::Currency).string().not_null())
+                    .col(
+                        ColumnDef::new(ExchangeRates::QuoteCurrency)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ExchangeRates::RateDate).date().not_null())
+                    .col(ColumnDef::new(ExchangeRates::Rate).decimal().not_null())
+                    .col(
+                        ColumnDef::new(ExchangeRates::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExchangeRates::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 同一天同一币种对只保留一条汇率
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_exchange_rates_pair_date")
+                    .table(ExchangeRates::Table)
+                    .col(ExchangeRates::Currency)
+                    .col(ExchangeRates::QuoteCurrency)
+                    .col(ExchangeRates::RateDate)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ExchangeRates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ExchangeRates {
+    Table,
+    Id,
+    Currency,
+    QuoteCurrency,
+    RateDate,
+    Rate,
+    CreatedAt,
+    UpdatedAt,
+}
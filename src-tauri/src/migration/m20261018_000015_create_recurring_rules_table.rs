@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 周期记录规则，到期后由后台任务生成 expense_records
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecurringRules::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RecurringRules::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringRules::RecordType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringRules::CategoryId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(RecurringRules::AccountId).integer())
+                    .col(ColumnDef::new(RecurringRules::Amount).decimal().not_null())
+                    .col(ColumnDef::new(RecurringRules::Currency).string().not_null())
+                    .col(ColumnDef::new(RecurringRules::Note).string())
+                    .col(
+                        ColumnDef::new(RecurringRules::Frequency)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(RecurringRules::IntervalDays).integer())
+                    .col(ColumnDef::new(RecurringRules::DayOfMonth).integer())
+                    .col(ColumnDef::new(RecurringRules::StartDate).date().not_null())
+                    .col(ColumnDef::new(RecurringRules::EndDate).date())
+                    .col(ColumnDef::new(RecurringRules::MaterializedUntil).date())
+                    .col(
+                        ColumnDef::new(RecurringRules::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringRules::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_rules_category")
+                            .from(RecurringRules::Table, RecurringRules::CategoryId)
+                            .to(Categories::Table, Categories::CategoryId)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RecurringRules::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum RecurringRules {
+    Table,
+    Id,
+    RecordType,
+    CategoryId,
+    AccountId,
+    Amount,
+    Currency,
+    Note,
+    Frequency,
+    IntervalDays,
+    DayOfMonth,
+    StartDate,
+    EndDate,
+    MaterializedUntil,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Categories {
+    Table,
+    CategoryId,
+}
@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 由周期规则生成的记录，记下规则和对应的周期日期
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExpenseRecords::Table)
+                    .add_column(ColumnDef::new(ExpenseRecords::RecurringRuleId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExpenseRecords::Table)
+                    .add_column(ColumnDef::new(ExpenseRecords::RecurringDate).date())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 同一规则每个周期只生成一条记录，重复补录时依靠该索引去重
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_expense_records_recurring")
+                    .table(ExpenseRecords::Table)
+                    .col(ExpenseRecords::RecurringRuleId)
+                    .col(ExpenseRecords::RecurringDate)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_expense_records_recurring")
+                    .table(ExpenseRecords::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExpenseRecords::Table)
+                    .drop_column(ExpenseRecords::RecurringDate)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExpenseRecords::Table)
+                    .drop_column(ExpenseRecords::RecurringRuleId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ExpenseRecords {
+    Table,
+    RecurringRuleId,
+    RecurringDate,
+}
@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 预算金额为本位币，category_id 为空表示全部支出
+        manager
+            .create_table(
+                Table::create()
+                    .table(Budgets::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Budgets::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Budgets::Period).string().not_null())
+                    .col(ColumnDef::new(Budgets::CategoryId).string())
+                    .col(ColumnDef::new(Budgets::Amount).decimal().not_null())
+                    .col(
+                        ColumnDef::new(Budgets::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Budgets::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_budgets_category")
+                            .from(Budgets::Table, Budgets::CategoryId)
+                            .to(Categories::Table, Categories::CategoryId)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Budgets::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Budgets {
+    Table,
+    Id,
+    Period,
+    CategoryId,
+    Amount,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Categories {
+    Table,
+    CategoryId,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 开启后上一周期的结余（或超支）结转到下一周期
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Budgets::Table)
+                    .add_column(
+                        ColumnDef::new(Budgets::Rollover)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Budgets::Table)
+                    .drop_column(Budgets::Rollover)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Budgets {
+    Table,
+    Rollover,
+}
@@ -0,0 +1,102 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 预算每个周期的额度与是否结转；周期结束时写入快照，之后修改预算不影响已结束的周期
+        // 信封分配会提前写入当前或之后月份的额度
+        manager
+            .create_table(
+                Table::create()
+                    .table(BudgetAllocations::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BudgetAllocations::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(BudgetAllocations::BudgetId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BudgetAllocations::StartDate)
+                            .date()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BudgetAllocations::Amount)
+                            .decimal()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BudgetAllocations::Rollover)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(BudgetAllocations::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BudgetAllocations::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_budget_allocations_budget")
+                            .from(BudgetAllocations::Table, BudgetAllocations::BudgetId)
+                            .to(Budgets::Table, Budgets::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_budget_allocations_budget_start")
+                    .table(BudgetAllocations::Table)
+                    .col(BudgetAllocations::BudgetId)
+                    .col(BudgetAllocations::StartDate)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BudgetAllocations::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum BudgetAllocations {
+    Table,
+    Id,
+    BudgetId,
+    StartDate,
+    Amount,
+    Rollover,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Budgets {
+    Table,
+    Id,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 使用比例达到这些百分比时提醒，JSON 数组
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Budgets::Table)
+                    .add_column(
+                        ColumnDef::new(Budgets::AlertThresholds)
+                            .json()
+                            .not_null()
+                            .default("[80,100]"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Budgets::Table)
+                    .drop_column(Budgets::AlertThresholds)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Budgets {
+    Table,
+    AlertThresholds,
+}
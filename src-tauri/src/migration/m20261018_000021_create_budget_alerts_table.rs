@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 已发出的预算提醒，每个预算每个周期每个阈值只提醒一次
+        manager
+            .create_table(
+                Table::create()
+                    .table(BudgetAlerts::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BudgetAlerts::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(BudgetAlerts::BudgetId).integer().not_null())
+                    .col(ColumnDef::new(BudgetAlerts::StartDate).date().not_null())
+                    .col(ColumnDef::new(BudgetAlerts::Threshold).integer().not_null())
+                    .col(
+                        ColumnDef::new(BudgetAlerts::PercentUsed)
+                            .decimal()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BudgetAlerts::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_budget_alerts_budget")
+                            .from(BudgetAlerts::Table, BudgetAlerts::BudgetId)
+                            .to(Budgets::Table, Budgets::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_budget_alerts_budget_period_threshold")
+                    .table(BudgetAlerts::Table)
+                    .col(BudgetAlerts::BudgetId)
+                    .col(BudgetAlerts::StartDate)
+                    .col(BudgetAlerts::Threshold)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BudgetAlerts::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum BudgetAlerts {
+    Table,
+    Id,
+    BudgetId,
+    StartDate,
+    Threshold,
+    PercentUsed,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Budgets {
+    Table,
+    Id,
+}
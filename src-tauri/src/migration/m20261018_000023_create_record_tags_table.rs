@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 记录与标签的多对多关联，删除记录或标签时一并删除
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecordTags::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RecordTags::RecordId).integer().not_null())
+                    .col(ColumnDef::new(RecordTags::TagId).integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(RecordTags::RecordId)
+                            .col(RecordTags::TagId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_record_tags_record")
+                            .from(RecordTags::Table, RecordTags::RecordId)
+                            .to(ExpenseRecords::Table, ExpenseRecords::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_record_tags_tag")
+                            .from(RecordTags::Table, RecordTags::TagId)
+                            .to(Tags::Table, Tags::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 按标签查记录
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_record_tags_tag")
+                    .table(RecordTags::Table)
+                    .col(RecordTags::TagId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RecordTags::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum RecordTags {
+    Table,
+    RecordId,
+    TagId,
+}
+
+#[derive(Iden)]
+enum ExpenseRecords {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Tags {
+    Table,
+    Id,
+}
@@ -0,0 +1,99 @@
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 记录全文索引：rowid 即记录 ID，索引备注、分类名称（含默认分类的翻译 key）和标签
+// trigram 分词按三个字符切分，中文不需要分词也能按子串匹配
+// 由触发器与记录、分类、标签同步；之后若重建 expense_records 表需要重新创建触发器
+const UP: &[&str] = &[
+    r#"CREATE VIRTUAL TABLE IF NOT EXISTS "record_search"
+        USING fts5("note", "category", "tags", tokenize = 'trigram')"#,
+    r#"CREATE TRIGGER IF NOT EXISTS "trg_record_search_insert"
+        AFTER INSERT ON "expense_records" BEGIN
+            INSERT INTO "record_search" ("rowid", "note", "category", "tags")
+            VALUES (NEW."id", COALESCE(NEW."note", ''),
+                COALESCE((SELECT "name" || ' ' || COALESCE("name_key", '') FROM "categories"
+                    WHERE "category_id" = NEW."category_id"), ''),
+                '');
+        END"#,
+    r#"CREATE TRIGGER IF NOT EXISTS "trg_record_search_update"
+        AFTER UPDATE OF "note", "category_id" ON "expense_records" BEGIN
+            UPDATE "record_search" SET
+                "note" = COALESCE(NEW."note", ''),
+                "category" = COALESCE((SELECT "name" || ' ' || COALESCE("name_key", '')
+                    FROM "categories" WHERE "category_id" = NEW."category_id"), '')
+            WHERE "rowid" = NEW."id";
+        END"#,
+    r#"CREATE TRIGGER IF NOT EXISTS "trg_record_search_delete"
+        AFTER DELETE ON "expense_records" BEGIN
+            DELETE FROM "record_search" WHERE "rowid" = OLD."id";
+        END"#,
+    r#"CREATE TRIGGER IF NOT EXISTS "trg_record_search_category"
+        AFTER UPDATE OF "name", "name_key" ON "categories" BEGIN
+            UPDATE "record_search" SET "category" = NEW."name" || ' ' || COALESCE(NEW."name_key", '')
+            WHERE "rowid" IN (SELECT "id" FROM "expense_records"
+                WHERE "category_id" = NEW."category_id");
+        END"#,
+    r#"CREATE TRIGGER IF NOT EXISTS "trg_record_search_tag_insert"
+        AFTER INSERT ON "record_tags" BEGIN
+            UPDATE "record_search" SET "tags" = COALESCE((SELECT group_concat("t"."name", ' ')
+                FROM "record_tags" AS "rt" JOIN "tags" AS "t" ON "t"."id" = "rt"."tag_id"
+                WHERE "rt"."record_id" = NEW."record_id"), '')
+            WHERE "rowid" = NEW."record_id";
+        END"#,
+    r#"CREATE TRIGGER IF NOT EXISTS "trg_record_search_tag_delete"
+        AFTER DELETE ON "record_tags" BEGIN
+            UPDATE "record_search" SET "tags" = COALESCE((SELECT group_concat("t"."name", ' ')
+                FROM "record_tags" AS "rt" JOIN "tags" AS "t" ON "t"."id" = "rt"."tag_id"
+                WHERE "rt"."record_id" = OLD."record_id"), '')
+            WHERE "rowid" = OLD."record_id";
+        END"#,
+    r#"CREATE TRIGGER IF NOT EXISTS "trg_record_search_tag_rename"
+        AFTER UPDATE OF "name" ON "tags" BEGIN
+            UPDATE "record_search" SET "tags" = COALESCE((SELECT group_concat("t"."name", ' ')
+                FROM "record_tags" AS "rt" JOIN "tags" AS "t" ON "t"."id" = "rt"."tag_id"
+                WHERE "rt"."record_id" = "record_search"."rowid"), '')
+            WHERE "rowid" IN (SELECT "record_id" FROM "record_tags" WHERE "tag_id" = NEW."id");
+        END"#,
+    // 为已有记录建立索引
+    r#"INSERT INTO "record_search" ("rowid", "note", "category", "tags")
+        SELECT "r"."id", COALESCE("r"."note", ''),
+            COALESCE("c"."name" || ' ' || COALESCE("c"."name_key", ''), ''),
+            COALESCE((SELECT group_concat("t"."name", ' ')
+                FROM "record_tags" AS "rt" JOIN "tags" AS "t" ON "t"."id" = "rt"."tag_id"
+                WHERE "rt"."record_id" = "r"."id"), '')
+        FROM "expense_records" AS "r"
+        LEFT JOIN "categories" AS "c" ON "c"."category_id" = "r"."category_id""#,
+];
+
+const DOWN: &[&str] = &[
+    r#"DROP TRIGGER IF EXISTS "trg_record_search_insert""#,
+    r#"DROP TRIGGER IF EXISTS "trg_record_search_update""#,
+    r#"DROP TRIGGER IF EXISTS "trg_record_search_delete""#,
+    r#"DROP TRIGGER IF EXISTS "trg_record_search_category""#,
+    r#"DROP TRIGGER IF EXISTS "trg_record_search_tag_insert""#,
+    r#"DROP TRIGGER IF EXISTS "trg_record_search_tag_delete""#,
+    r#"DROP TRIGGER IF EXISTS "trg_record_search_tag_rename""#,
+    r#"DROP TABLE IF EXISTS "record_search""#,
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(manager, UP).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(manager, DOWN).await
+    }
+}
+
+async fn execute_all(manager: &SchemaManager<'_>, statements: &[&str]) -> Result<(), DbErr> {
+    let txn = manager.get_connection().begin().await?;
+    for statement in statements {
+        txn.execute_unprepared(statement).await?;
+    }
+    txn.commit().await
+}
@@ -0,0 +1,150 @@
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 全文索引的分类列改为保存各语言的显示名称：数据库中的名称加上默认分类的繁体和英文翻译，
+// 不再索引翻译 key，英文界面搜索 Rent 等名称也能找到记录
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        recreate_category_triggers(manager, localized_names).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        recreate_category_triggers(manager, names_with_key).await
+    }
+}
+
+// 000024 的规则：名称加翻译 key
+fn names_with_key(alias: &str) -> String {
+    format!(
+        r#"{0}."name" || ' ' || COALESCE({0}."name_key", '')"#,
+        alias
+    )
+}
+
+// 写入迁移时默认分类的翻译：(name_key, 繁体中文, 英文)，之后翻译有变化需新增迁移
+const CATEGORY_NAMES: &[(&str, &str, &str)] = &[
+    ("food", "餐飲", "Food"),
+    ("shopping", "購物", "Shopping"),
+    ("daily", "日用", "Daily Necessities"),
+    ("transport", "交通", "Transport"),
+    ("vegetables", "蔬菜", "Vegetables"),
+    ("fruits", "水果", "Fruits"),
+    ("snacks", "零食", "Snacks"),
+    ("sports", "運動", "Sports"),
+    ("entertainment", "娛樂", "Entertainment"),
+    ("communication", "通訊", "Communication"),
+    ("clothing", "服飾", "Clothing"),
+    ("beauty", "美容", "Beauty"),
+    ("housing", "住房", "Housing"),
+    ("household", "居家", "Household"),
+    ("children", "孩子", "Children"),
+    ("elderly", "長輩", "Elders"),
+    ("social", "社交", "Social"),
+    ("travel", "旅行", "Travel"),
+    ("tobacco", "菸酒", "Tobacco & Alcohol"),
+    ("digital", "數位", "Electronics"),
+    ("car", "汽車", "Car"),
+    ("medical", "醫療", "Medical"),
+    ("books", "書籍", "Books"),
+    ("study", "學習", "Education"),
+    ("pets", "寵物", "Pets"),
+    ("gift_money", "禮金", "Gift Money"),
+    ("gifts", "禮物", "Gifts"),
+    ("office", "辦公", "Office"),
+    ("repair", "維修", "Repairs"),
+    ("donation", "捐贈", "Donations"),
+    ("lottery", "彩券", "Lottery"),
+    ("friends", "親友", "Family & Friends"),
+    ("express", "快遞", "Delivery"),
+    ("settings", "設定", "Settings"),
+    ("salary", "薪資", "Salary"),
+    ("bonus", "獎金", "Bonus"),
+    ("investment", "投資", "Investment"),
+    ("part_time", "兼職", "Part-time"),
+    ("gift", "禮金", "Gift Money"),
+    ("other", "其他", "Other"),
+];
+
+// alias 所指分类的索引文本；用户改名后 name_key 为空，只索引新名称
+fn localized_names(alias: &str) -> String {
+    let cases: String = CATEGORY_NAMES
+        .iter()
+        .map(|(key, zh_tw, en)| {
+            format!(
+                " WHEN {} THEN ' ' || {} || ' ' || {}",
+                quote(key),
+                quote(zh_tw),
+                quote(en)
+            )
+        })
+        .collect();
+
+    format!(
+        r#"{0}."name" || CASE {0}."name_key"{1} ELSE '' END"#,
+        alias, cases
+    )
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+// 重建与分类名称有关的三个触发器，并按新的规则刷新已有索引
+async fn recreate_category_triggers(
+    manager: &SchemaManager<'_>,
+    category_text: fn(&str) -> String,
+) -> Result<(), DbErr> {
+    let statements = [
+        r#"DROP TRIGGER IF EXISTS "trg_record_search_insert""#.to_string(),
+        r#"DROP TRIGGER IF EXISTS "trg_record_search_update""#.to_string(),
+        r#"DROP TRIGGER IF EXISTS "trg_record_search_category""#.to_string(),
+        format!(
+            r#"CREATE TRIGGER "trg_record_search_insert"
+                AFTER INSERT ON "expense_records" BEGIN
+                    INSERT INTO "record_search" ("rowid", "note", "category", "tags")
+                    VALUES (NEW."id", COALESCE(NEW."note", ''),
+                        COALESCE((SELECT {} FROM "categories" AS "c"
+                            WHERE "c"."category_id" = NEW."category_id"), ''),
+                        '');
+                END"#,
+            category_text(r#""c""#)
+        ),
+        format!(
+            r#"CREATE TRIGGER "trg_record_search_update"
+                AFTER UPDATE OF "note", "category_id" ON "expense_records" BEGIN
+                    UPDATE "record_search" SET
+                        "note" = COALESCE(NEW."note", ''),
+                        "category" = COALESCE((SELECT {} FROM "categories" AS "c"
+                            WHERE "c"."category_id" = NEW."category_id"), '')
+                    WHERE "rowid" = NEW."id";
+                END"#,
+            category_text(r#""c""#)
+        ),
+        format!(
+            r#"CREATE TRIGGER "trg_record_search_category"
+                AFTER UPDATE OF "name", "name_key" ON "categories" BEGIN
+                    UPDATE "record_search" SET "category" = {}
+                    WHERE "rowid" IN (SELECT "id" FROM "expense_records"
+                        WHERE "category_id" = NEW."category_id");
+                END"#,
+            category_text("NEW")
+        ),
+        format!(
+            r#"UPDATE "record_search" SET "category" = COALESCE((SELECT {}
+                FROM "expense_records" AS "r" JOIN "categories" AS "c"
+                    ON "c"."category_id" = "r"."category_id"
+                WHERE "r"."id" = "record_search"."rowid"), '')"#,
+            category_text(r#""c""#)
+        ),
+    ];
+
+    let txn = manager.get_connection().begin().await?;
+    for statement in statements {
+        txn.execute_unprepared(&statement).await?;
+    }
+    txn.commit().await
+}
@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 引入翻译 key 之前写入的默认分类：(category_id, 当时的默认名称)
+// 名称仍与默认名称相同的补上与 category_id 相同的 key，改过名的保持为空
+const DEFAULT_NAMES: &[(&str, &str)] = &[
+    ("food", "餐饮"),
+    ("shopping", "购物"),
+    ("daily", "日用"),
+    ("transport", "交通"),
+    ("vegetables", "蔬菜"),
+    ("fruits", "水果"),
+    ("snacks", "零食"),
+    ("sports", "运动"),
+    ("entertainment", "娱乐"),
+    ("communication", "通讯"),
+    ("clothing", "服饰"),
+    ("beauty", "美容"),
+    ("housing", "住房"),
+    ("household", "居家"),
+    ("children", "孩子"),
+    ("elderly", "长辈"),
+    ("social", "社交"),
+    ("travel", "旅行"),
+    ("tobacco", "烟酒"),
+    ("digital", "数码"),
+    ("car", "汽车"),
+    ("medical", "医疗"),
+    ("books", "书籍"),
+    ("study", "学习"),
+    ("pets", "宠物"),
+    ("gift_money", "礼金"),
+    ("gifts", "礼物"),
+    ("office", "办公"),
+    ("repair", "维修"),
+    ("donation", "捐赠"),
+    ("lottery", "彩票"),
+    ("friends", "亲友"),
+    ("express", "快递"),
+    ("settings", "设置"),
+    ("salary", "工资"),
+    ("bonus", "奖金"),
+    ("investment", "投资"),
+    ("part_time", "兼职"),
+    ("gift", "礼金"),
+    ("other", "其他"),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (category_id, name) in DEFAULT_NAMES {
+            manager
+                .exec_stmt(
+                    Query::update()
+                        .table(Categories::Table)
+                        .value(Categories::NameKey, *category_id)
+                        .and_where(Expr::col(Categories::CategoryId).eq(*category_id))
+                        .and_where(Expr::col(Categories::Name).eq(*name))
+                        .and_where(Expr::col(Categories::NameKey).is_null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // 补上的 key 与新写入的默认分类无法区分，保持不变
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Categories {
+    Table,
+    CategoryId,
+    Name,
+    NameKey,
+}
@@ -0,0 +1,273 @@
+use sea_orm::{ConnectionTrait, Statement, TransactionTrait};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 为 account_id、to_account_id 补上指向 accounts 的外键，仍被记录引用的账户不能删除
+// SQLite 只能重建 expense_records 表来添加外键
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild_expense_records(manager, true).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild_expense_records(manager, false).await
+    }
+}
+
+// 删除旧表时会按外键级联删除 record_tags，级联触发的索引更新会使删除失败，先备份并清空再写回；
+// 全文索引的触发器引用 expense_records，重建前从 sqlite_master 取出原定义并删除，重建后按原样创建，
+// 记录 id 不变，索引内容无需刷新
+async fn rebuild_expense_records(
+    manager: &SchemaManager<'_>,
+    with_account_keys: bool,
+) -> Result<(), DbErr> {
+    let txn = manager.get_connection().begin().await?;
+    let manager = SchemaManager::new(&txn);
+
+    let mut table = Table::create();
+    table
+        .table(ExpenseRecordsNew::Table)
+        .col(
+            ColumnDef::new(ExpenseRecords::Id)
+                .integer()
+                .not_null()
+                .auto_increment()
+                .primary_key(),
+        )
+        .col(
+            ColumnDef::new(ExpenseRecords::RecordType)
+                .string()
+                .not_null(),
+        )
+        .col(ColumnDef::new(ExpenseRecords::CategoryId).string())
+        .col(ColumnDef::new(ExpenseRecords::AccountId).integer())
+        .col(ColumnDef::new(ExpenseRecords::ToAccountId).integer())
+        .col(ColumnDef::new(ExpenseRecords::Amount).decimal().not_null())
+        .col(
+            ColumnDef::new(ExpenseRecords::Currency)
+                .string()
+                .not_null()
+                .default("CNY"),
+        )
+        .col(ColumnDef::new(ExpenseRecords::Fee).decimal())
+        .col(ColumnDef::new(ExpenseRecords::Note).string())
+        .col(
+            ColumnDef::new(ExpenseRecords::OccurredAt)
+                .timestamp_with_time_zone()
+                .not_null(),
+        )
+        .col(
+            ColumnDef::new(ExpenseRecords::CreatedAt)
+                .timestamp_with_time_zone()
+                .not_null(),
+        )
+        .col(
+            ColumnDef::new(ExpenseRecords::UpdatedAt)
+                .timestamp_with_time_zone()
+                .not_null(),
+        )
+        .col(ColumnDef::new(ExpenseRecords::DeletedAt).timestamp_with_time_zone())
+        .col(ColumnDef::new(ExpenseRecords::RecurringRuleId).integer())
+        .col(ColumnDef::new(ExpenseRecords::RecurringDate).date())
+        .foreign_key(
+            ForeignKey::create()
+                .name("fk_expense_records_category")
+                .from(ExpenseRecordsNew::Table, ExpenseRecords::CategoryId)
+                .to(Categories::Table, Categories::CategoryId)
+                .on_delete(ForeignKeyAction::Restrict)
+                .on_update(ForeignKeyAction::Cascade),
+        );
+    if with_account_keys {
+        table
+            .foreign_key(
+                ForeignKey::create()
+                    .name("fk_expense_records_account")
+                    .from(ExpenseRecordsNew::Table, ExpenseRecords::AccountId)
+                    .to(Accounts::Table, Accounts::Id)
+                    .on_delete(ForeignKeyAction::Restrict),
+            )
+            .foreign_key(
+                ForeignKey::create()
+                    .name("fk_expense_records_to_account")
+                    .from(ExpenseRecordsNew::Table, ExpenseRecords::ToAccountId)
+                    .to(Accounts::Table, Accounts::Id)
+                    .on_delete(ForeignKeyAction::Restrict),
+            );
+
+        // 账户删除由 account_service 校验，正常不会有悬空引用；万一存在则清空，避免复制时违反外键
+        for column in [ExpenseRecords::AccountId, ExpenseRecords::ToAccountId] {
+            manager
+                .exec_stmt(
+                    Query::update()
+                        .table(ExpenseRecords::Table)
+                        .value(column.clone(), Option::<i32>::None)
+                        .and_where(Expr::col(column.clone()).is_not_null())
+                        .and_where(
+                            Expr::col(column).not_in_subquery(
+                                Query::select()
+                                    .column(Accounts::Id)
+                                    .from(Accounts::Table)
+                                    .to_owned(),
+                            ),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+    }
+    manager.create_table(table).await?;
+
+    let columns = vec![
+        ExpenseRecords::Id,
+        ExpenseRecords::RecordType,
+        ExpenseRecords::CategoryId,
+        ExpenseRecords::AccountId,
+        ExpenseRecords::ToAccountId,
+        ExpenseRecords::Amount,
+        ExpenseRecords::Currency,
+        ExpenseRecords::Fee,
+        ExpenseRecords::Note,
+        ExpenseRecords::OccurredAt,
+        ExpenseRecords::CreatedAt,
+        ExpenseRecords::UpdatedAt,
+        ExpenseRecords::DeletedAt,
+        ExpenseRecords::RecurringRuleId,
+        ExpenseRecords::RecurringDate,
+    ];
+    manager
+        .exec_stmt(
+            Query::insert()
+                .into_table(ExpenseRecordsNew::Table)
+                .columns(columns.clone())
+                .select_from(
+                    Query::select()
+                        .columns(columns)
+                        .from(ExpenseRecords::Table)
+                        .to_owned(),
+                )
+                .map_err(|e| DbErr::Migration(e.to_string()))?
+                .to_owned(),
+        )
+        .await?;
+
+    let triggers = txn
+        .query_all(Statement::from_string(
+            txn.get_database_backend(),
+            r#"SELECT "name", "sql" FROM "sqlite_master"
+                WHERE "type" = 'trigger' AND "sql" LIKE '%"expense_records"%'"#,
+        ))
+        .await?
+        .into_iter()
+        .map(|row| Ok((row.try_get::<String>("", "name")?, row.try_get("", "sql")?)))
+        .collect::<Result<Vec<(String, String)>, DbErr>>()?;
+    for (name, _) in &triggers {
+        txn.execute_unprepared(&format!(r#"DROP TRIGGER "{}""#, name))
+            .await?;
+    }
+    for statement in [
+        r#"CREATE TEMP TABLE "record_tags_backup" AS SELECT * FROM "record_tags""#,
+        r#"DELETE FROM "record_tags""#,
+    ] {
+        txn.execute_unprepared(statement).await?;
+    }
+
+    manager
+        .drop_table(Table::drop().table(ExpenseRecords::Table).to_owned())
+        .await?;
+
+    manager
+        .rename_table(
+            Table::rename()
+                .table(ExpenseRecordsNew::Table, ExpenseRecords::Table)
+                .to_owned(),
+        )
+        .await?;
+
+    // 索引随旧表一起删除，需要重建
+    let indexes = [
+        (
+            "idx_expense_records_occurred_at",
+            ExpenseRecords::OccurredAt,
+        ),
+        ("idx_expense_records_deleted_at", ExpenseRecords::DeletedAt),
+        ("idx_expense_records_account_id", ExpenseRecords::AccountId),
+        (
+            "idx_expense_records_to_account_id",
+            ExpenseRecords::ToAccountId,
+        ),
+    ];
+    for (name, column) in indexes {
+        manager
+            .create_index(
+                Index::create()
+                    .name(name)
+                    .table(ExpenseRecords::Table)
+                    .col(column)
+                    .to_owned(),
+            )
+            .await?;
+    }
+    manager
+        .create_index(
+            Index::create()
+                .name("idx_expense_records_recurring")
+                .table(ExpenseRecords::Table)
+                .col(ExpenseRecords::RecurringRuleId)
+                .col(ExpenseRecords::RecurringDate)
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+    for statement in [
+        r#"INSERT INTO "record_tags" SELECT * FROM "record_tags_backup""#,
+        r#"DROP TABLE "record_tags_backup""#,
+    ] {
+        txn.execute_unprepared(statement).await?;
+    }
+    for (_, sql) in &triggers {
+        txn.execute_unprepared(sql).await?;
+    }
+
+    txn.commit().await
+}
+
+#[derive(Iden, Clone)]
+enum ExpenseRecords {
+    Table,
+    Id,
+    RecordType,
+    CategoryId,
+    AccountId,
+    ToAccountId,
+    Amount,
+    Currency,
+    Fee,
+    Note,
+    OccurredAt,
+    CreatedAt,
+    UpdatedAt,
+    DeletedAt,
+    RecurringRuleId,
+    RecurringDate,
+}
+
+#[derive(Iden)]
+enum ExpenseRecordsNew {
+    Table,
+}
+
+#[derive(Iden)]
+enum Categories {
+    Table,
+    CategoryId,
+}
+
+#[derive(Iden)]
+enum Accounts {
+    Table,
+    Id,
+}
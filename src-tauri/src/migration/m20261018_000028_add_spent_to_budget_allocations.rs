@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 周期结束时记下当期支出，之后移动分类或修改汇率不再改变历史结转；
+        // 已有快照在下次结束周期时补写
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BudgetAllocations::Table)
+                    .add_column(ColumnDef::new(BudgetAllocations::Spent).decimal())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BudgetAllocations::Table)
+                    .drop_column(BudgetAllocations::Spent)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum BudgetAllocations {
+    Table,
+    Spent,
+}
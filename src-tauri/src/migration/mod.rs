@@ -2,6 +2,34 @@ pub use sea_orm_migration::prelude::*;
 
 mod m20231212_000001_create_categories_table;
 mod m20231212_000002_create_expense_records_table;
+mod m20261018_000001_add_occurred_at_to_expense_records;
+mod m20261018_000002_normalize_record_and_category_types;
+mod m20261018_000003_add_deleted_at_to_expense_records;
+mod m20261018_000004_restrict_category_delete_on_expense_records;
+mod m20261018_000005_add_archived_to_categories;
+mod m20261018_000006_add_parent_id_to_categories;
+mod m20261018_000007_add_sort_order_to_categories;
+mod m20261018_000008_create_app_settings_table;
+mod m20261018_000009_add_name_key_to_categories;
+mod m20261018_000010_create_accounts_table;
+mod m20261018_000011_add_account_id_to_expense_records;
+mod m20261018_000012_add_transfers_to_expense_records;
+mod m20261018_000013_add_currency_to_expense_records;
+mod m20261018_000014_create_exchange_rates_table;
+mod m20261018_000015_create_recurring_rules_table;
+mod m20261018_000016_add_recurring_rule_to_expense_records;
+mod m20261018_000017_create_budgets_table;
+mod m20261018_000018_add_rollover_to_budgets;
+mod m20261018_000019_create_budget_allocations_table;
+mod m20261018_000020_add_alert_thresholds_to_budgets;
+mod m20261018_000021_create_budget_alerts_table;
+mod m20261018_000022_create_tags_table;
+mod m20261018_000023_create_record_tags_table;
+mod m20261018_000024_create_record_search_table;
+mod m20261018_000025_index_localized_category_names;
+mod m20261018_000026_backfill_category_name_keys;
+mod m20261018_000027_add_account_foreign_keys_to_expense_records;
+mod m20261018_000028_add_spent_to_budget_allocations;
 
 pub struct Migrator;
 
@@ -11,6 +39,244 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20231212_000001_create_categories_table::Migration),
             Box::new(m20231212_000002_create_expense_records_table::Migration),
+            Box::new(m20261018_000001_add_occurred_at_to_expense_records::Migration),
+            Box::new(m20261018_000002_normalize_record_and_category_types::Migration),
+            Box::new(m20261018_000003_add_deleted_at_to_expense_records::Migration),
+            Box::new(m20261018_000004_restrict_category_delete_on_expense_records::Migration),
+            Box::new(m20261018_000005_add_archived_to_categories::Migration),
+            Box::new(m20261018_000006_add_parent_id_to_categories::Migration),
+            Box::new(m20261018_000007_add_sort_order_to_categories::Migration),
+            Box::new(m20261018_000008_create_app_settings_table::Migration),
+            Box::new(m20261018_000009_add_name_key_to_categories::Migration),
+            Box::new(m20261018_000010_create_accounts_table::Migration),
+            Box::new(m20261018_000011_add_account_id_to_expense_records::Migration),
+            Box::new(m20261018_000012_add_transfers_to_expense_records::Migration),
+            Box::new(m20261018_000013_add_currency_to_expense_records::Migration),
+            Box::new(m20261018_000014_create_exchange_rates_table::Migration),
+            Box::new(m20261018_000015_create_recurring_rules_table::Migration),
+            Box::new(m20261018_000016_add_recurring_rule_to_expense_records::Migration),
+            Box::new(m20261018_000017_create_budgets_table::Migration),
+            Box::new(m20261018_000018_add_rollover_to_budgets::Migration),
+            Box::new(m20261018_000019_create_budget_allocations_table::Migration),
+            Box::new(m20261018_000020_add_alert_thresholds_to_budgets::Migration),
+            Box::new(m20261018_000021_create_budget_alerts_table::Migration),
+            Box::new(m20261018_000022_create_tags_table::Migration),
+            Box::new(m20261018_000023_create_record_tags_table::Migration),
+            Box::new(m20261018_000024_create_record_search_table::Migration),
+            Box::new(m20261018_000025_index_localized_category_names::Migration),
+            Box::new(m20261018_000026_backfill_category_name_keys::Migration),
+            Box::new(m20261018_000027_add_account_foreign_keys_to_expense_records::Migration),
+            Box::new(m20261018_000028_add_spent_to_budget_allocations::Migration),
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::record_service::{self, ExpenseRecordData};
+    use crate::database::test_connection;
+    use crate::database::test_util::record_data;
+    use crate::entities::RecordType;
+    use rust_decimal::Decimal;
+    use sea_orm::{ConnectionTrait, Database, DatabaseConnection, Statement};
+
+    // 执行到 name 为止（含）需要的步数
+    fn steps_through(name: &str) -> u32 {
+        let position = Migrator::migrations()
+            .iter()
+            .position(|migration| migration.name() == name)
+            .unwrap();
+        position as u32 + 1
+    }
+
+    // 回滚到 name 之前需要执行的步数
+    fn steps_before(name: &str) -> u32 {
+        let position = Migrator::migrations()
+            .iter()
+            .rev()
+            .position(|migration| migration.name() == name)
+            .unwrap();
+        position as u32 + 1
+    }
+
+    async fn query_strings(db: &DatabaseConnection, sql: &str, column: &str) -> Vec<String> {
+        db.query_all(Statement::from_string(db.get_database_backend(), sql))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.try_get("", column).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn normalize_types_falls_back_to_expense_without_category() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(
+            &db,
+            Some(steps_through(
+                "m20261018_000001_add_occurred_at_to_expense_records",
+            )),
+        )
+        .await
+        .unwrap();
+
+        // 分类已不存在的记录只会出现在外键未生效时写入的旧数据中
+        for statement in [
+            r#"PRAGMA foreign_keys = OFF"#,
+            r#"INSERT INTO "categories" ("category_id", "name", "icon", "color", "category_type", "created_at", "updated_at")
+                VALUES ('salary', '工资', 'work', 'green', ' Income', '2024-01-01', '2024-01-01')"#,
+            r#"INSERT INTO "expense_records" ("record_type", "category_id", "amount", "created_at", "updated_at", "occurred_at")
+                VALUES ('Expense ', 'salary', 1, '2024-01-01', '2024-01-01', '2024-01-01'),
+                       ('bonus', 'salary', 2, '2024-01-01', '2024-01-01', '2024-01-01'),
+                       ('bonus', 'removed', 3, '2024-01-01', '2024-01-01', '2024-01-01')"#,
+            r#"PRAGMA foreign_keys = ON"#,
+        ] {
+            db.execute_unprepared(statement).await.unwrap();
+        }
+
+        Migrator::up(&db, Some(1)).await.unwrap();
+        assert_eq!(
+            query_strings(
+                &db,
+                r#"SELECT "record_type" FROM "expense_records" ORDER BY "id""#,
+                "record_type"
+            )
+            .await,
+            ["expense", "income", "expense"]
+        );
+    }
+
+    async fn seed_records(db: &DatabaseConnection) {
+        for (record_type, category_id, note) in [
+            (RecordType::Expense, "food", "lunch"),
+            (RecordType::Income, "salary", "october salary"),
+        ] {
+            record_service::create_record(
+                db,
+                ExpenseRecordData {
+                    note: Some(note.into()),
+                    tags: Some(vec!["work".into()]),
+                    ..record_data(record_type, category_id, Decimal::new(1250, 2), None)
+                },
+            )
+            .await
+            .unwrap();
+        }
+    }
+
+    async fn notes(db: &DatabaseConnection) -> Vec<String> {
+        query_strings(
+            db,
+            r#"SELECT "note" FROM "expense_records" ORDER BY "id""#,
+            "note",
+        )
+        .await
+    }
+
+    // 重建 expense_records 表的迁移来回执行后记录保持不变，全文索引也能重新建立
+    async fn assert_round_trip(name: &str) {
+        let db = test_connection().await;
+        seed_records(&db).await;
+
+        Migrator::down(&db, Some(steps_before(name))).await.unwrap();
+        assert_eq!(notes(&db).await, ["lunch", "october salary"]);
+
+        Migrator::up(&db, None).await.unwrap();
+        assert_eq!(notes(&db).await, ["lunch", "october salary"]);
+        let page = record_service::search_records(&db, "salary", None)
+            .await
+            .unwrap();
+        assert_eq!(page.total, 1);
+    }
+
+    #[tokio::test]
+    async fn restrict_category_delete_round_trip() {
+        let name = "m20261018_000004_restrict_category_delete_on_expense_records";
+        assert_round_trip(name).await;
+
+        // 仍有记录的分类不能删除
+        let db = test_connection().await;
+        seed_records(&db).await;
+        let result = db
+            .execute_unprepared(r#"DELETE FROM "categories" WHERE "category_id" = 'food'"#)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn transfers_round_trip() {
+        assert_round_trip("m20261018_000012_add_transfers_to_expense_records").await;
+    }
+
+    #[tokio::test]
+    async fn backfill_name_keys_skips_renamed_categories() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(
+            &db,
+            Some(steps_through(
+                "m20261018_000025_index_localized_category_names",
+            )),
+        )
+        .await
+        .unwrap();
+
+        // 引入翻译 key 之前写入的默认分类，其中 shopping 已被改名
+        db.execute_unprepared(
+            r#"INSERT INTO "categories" ("category_id", "name", "icon", "color", "category_type", "created_at", "updated_at")
+                VALUES ('food', '餐饮', 'restaurant', 'orange', 'expense', '2024-01-01', '2024-01-01'),
+                       ('shopping', '网购', 'shopping_cart', 'pink', 'expense', '2024-01-01', '2024-01-01'),
+                       ('coffee', '咖啡', 'local_cafe', 'brown', 'expense', '2024-01-01', '2024-01-01')"#,
+        )
+        .await
+        .unwrap();
+
+        Migrator::up(&db, None).await.unwrap();
+        assert_eq!(
+            query_strings(
+                &db,
+                r#"SELECT COALESCE("name_key", '') AS "name_key" FROM "categories" ORDER BY "id""#,
+                "name_key"
+            )
+            .await,
+            ["food", "", ""]
+        );
+    }
+
+    #[tokio::test]
+    async fn account_foreign_keys_round_trip() {
+        let name = "m20261018_000027_add_account_foreign_keys_to_expense_records";
+        assert_round_trip(name).await;
+
+        // 仍被记录引用的账户不能删除，标签的全文索引在重建后保留
+        let db = test_connection().await;
+        seed_records(&db).await;
+        db.execute_unprepared(
+            r#"INSERT INTO "accounts" ("name", "account_type", "opening_balance", "currency", "created_at", "updated_at")
+                VALUES ('钱包', 'cash', 0, 'CNY', '2024-01-01', '2024-01-01')"#,
+        )
+        .await
+        .unwrap();
+        db.execute_unprepared(r#"UPDATE "expense_records" SET "account_id" = 1"#)
+            .await
+            .unwrap();
+        Migrator::down(&db, Some(steps_before(name))).await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+
+        let result = db
+            .execute_unprepared(r#"DELETE FROM "accounts" WHERE "id" = 1"#)
+            .await;
+        assert!(result.is_err());
+        let page = record_service::search_records(&db, "work", None)
+            .await
+            .unwrap();
+        assert_eq!(page.total, 2);
+
+        // 触发器按原定义重新创建，新记录照常进入索引
+        seed_records(&db).await;
+        let page = record_service::search_records(&db, "lunch", None)
+            .await
+            .unwrap();
+        assert_eq!(page.total, 2);
+    }
+}
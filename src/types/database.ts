@@ -4,9 +4,13 @@ export interface Category {
   id?: number;
   category_id: string;
   name: string;
+  name_key?: string | null; // 默认分类的翻译 key，用户新建或改名后为空
   icon: string;
   color: string;
   category_type: "expense" | "income";
+  parent_id?: string | null; // 父分类的 category_id，顶级分类为空
+  archived?: boolean; // 归档后不在选择列表中显示
+  sort_order?: number; // 自定义顺序，数值越小越靠前
   created_at?: string;
   updated_at?: string;
 }
@@ -17,30 +21,342 @@ export interface CategoryData {
   icon: string;
   color: string;
   category_type: "expense" | "income";
+  parent_id?: string | null; // 仅在新建时使用，之后通过 move_category 调整
 }
 
+// 分类列表排序：manual 自定义顺序，usage 最近 90 天使用次数
+export type CategorySort = "manual" | "usage";
+
+// 分类树节点
+export interface CategoryNode extends Category {
+  children: CategoryNode[];
+}
+
+// 删除仍有记录的分类时的处理方式，默认 block
+export type CategoryDeleteMode =
+  | { mode: "block" }
+  | { mode: "reassign"; target_category_id: string }
+  | { mode: "archive" };
+
+export type AccountType =
+  | "cash"
+  | "bank_card"
+  | "credit_card"
+  | "alipay"
+  | "wechat_pay";
+
+export interface Account {
+  id: number;
+  name: string;
+  account_type: AccountType;
+  opening_balance: string; // 开户余额，信用卡欠款为负数
+  currency: string; // ISO 4217 货币代码
+  created_at?: string;
+  updated_at?: string;
+}
+
+export interface AccountData {
+  name: string;
+  account_type: AccountType;
+  opening_balance: string;
+  currency: string;
+}
+
+// 账户当前余额 = 开户余额 + 收入 - 支出 + 转入 - 转出
+export interface AccountBalance extends Account {
+  income_total: string;
+  expense_total: string;
+  transfer_in_total: string;
+  transfer_out_total: string; // 含手续费
+  balance: string;
+}
+
+// transfer 为账户间转账，不计入收支统计
+export type RecordType = "expense" | "income" | "transfer";
+
 export interface ExpenseRecord {
   id?: number;
-  record_type: "expense" | "income";
-  category_id: string;
+  record_type: RecordType;
+  category_id: string | null; // 转账记录没有分类
+  account_id?: number | null; // 所属账户；转账时为转出账户
+  to_account_id?: number | null; // 转账的转入账户
   amount: string; // Decimal 作为字符串传输
+  currency: string; // ISO 4217 货币代码
+  fee?: string | null; // 转账手续费，从转出账户扣除
   note?: string | null;
+  occurred_at?: string; // 实际发生时间
   created_at?: string;
   updated_at?: string;
+  deleted_at?: string | null; // 移入回收站的时间
+  recurring_rule_id?: number | null; // 生成该记录的周期规则
+  recurring_date?: string | null; // 对应的周期日期 YYYY-MM-DD
+  tags?: string[]; // 按名称排列；回收站与统计结果中的记录不带标签
 }
 
 export interface ExpenseRecordData {
   record_type: "expense" | "income";
   category_id: string;
+  account_id?: number | null;
+  amount: string;
+  currency?: string; // 缺省取账户币种，没有账户时取本位币
+  note?: string | null;
+  occurred_at?: string; // ISO 8601 格式，缺省为当前时间
+  tags?: string[]; // 不存在的标签自动创建；修改时整体替换，缺省保持不变
+}
+
+export interface Tag {
+  id: number;
+  name: string;
+  created_at?: string;
+}
+
+export interface TagData {
+  name: string;
+}
+
+export type BudgetPeriod = "weekly" | "monthly" | "yearly";
+
+// 预算金额为本位币
+export interface Budget {
+  id: number;
+  period: BudgetPeriod;
+  category_id?: string | null; // 为空表示全部支出，否则包含其子分类
+  amount: string; // 信封分配过的月份以分配额为准
+  rollover: boolean; // 结余或超支结转到下一周期
+  alert_thresholds: number[]; // 提醒阈值（百分比），从小到大排列
+  created_at?: string;
+  updated_at?: string;
+}
+
+// 已有历史周期的预算不能再修改 period 和 category_id
+export interface BudgetData {
+  period: BudgetPeriod;
+  category_id?: string | null;
+  amount: string;
+  rollover?: boolean; // 仅月、年预算支持；修改时缺省保持不变
+  alert_thresholds?: number[]; // 1-500，最多 5 个；创建时缺省为 [80, 100]，修改时缺省保持不变
+}
+
+// 某个周期的额度快照或信封分配额
+export interface BudgetAllocation {
+  id: number;
+  budget_id: number;
+  start_date: string; // YYYY-MM-DD
+  amount: string;
+  rollover: boolean;
+  spent?: string | null; // 周期结束时的支出，结束前为空
+  created_at?: string;
+  updated_at?: string;
+}
+
+export interface EnvelopeAllocation {
+  budget_id: number;
+  amount: string;
+}
+
+// 预算在当前周期内的执行情况
+export interface BudgetStatus extends Budget {
+  start_date: string; // 周期首日 YYYY-MM-DD
+  end_date: string; // 周期最后一天 YYYY-MM-DD
+  allocated: string; // 本周期额度
+  carried_in: string; // 上一周期结转，超支时为负数
+  available: string; // 额度 + 结转
+  spent: string;
+  remaining: string; // 超支时为负数
+  percent_used: string;
+  projected_spend: string; // 按日均支出推算的周期末总支出
+  missing_rates: string[]; // 缺少汇率、未计入支出的记录币种
+}
+
+// budget-alert 事件内容：预算在本周期首次达到某个提醒阈值
+export interface BudgetAlertNotice {
+  id: number;
+  budget_id: number;
+  start_date: string; // 周期首日 YYYY-MM-DD
+  threshold: number;
+  percent_used: string;
+  created_at?: string;
+  period: BudgetPeriod;
+  category_id?: string | null;
+  category_name?: string | null; // 总预算为空
+  spent: string;
+  available: string;
+}
+
+// 信封模式：当月收入分配到各月度分类预算
+export interface EnvelopeSummary {
+  start_date: string;
+  end_date: string;
+  income: string;
+  allocated: string;
+  unallocated: string; // 分配超出收入时为负数
+  budgets: BudgetStatus[];
+  missing_rates: string[]; // 缺少汇率、未计入收入的记录币种
+}
+
+// monthly 在当月没有该日时取月末；yearly 的 2 月 29 日在平年取 28 日
+export type RecurrenceFrequency =
+  | "daily"
+  | "weekly"
+  | "monthly"
+  | "yearly"
+  | "every_n_days";
+
+export interface RecurringRule {
+  id: number;
+  record_type: "expense" | "income";
+  category_id: string;
+  account_id?: number | null;
   amount: string;
+  currency: string;
   note?: string | null;
+  frequency: RecurrenceFrequency;
+  interval_days?: number | null; // every_n_days 的间隔天数
+  day_of_month?: number | null; // monthly 的日期
+  start_date: string; // YYYY-MM-DD
+  end_date?: string | null; // 为空表示一直重复
+  materialized_until?: string | null; // 已生成到该日期（含）
+  created_at?: string;
+  updated_at?: string;
+}
+
+export interface RecurringRuleData {
+  record_type: "expense" | "income";
+  category_id: string;
+  account_id?: number | null;
+  amount: string;
+  currency?: string; // 缺省取账户币种，没有账户时取本位币
+  note?: string | null;
+  frequency: RecurrenceFrequency;
+  interval_days?: number | null; // 仅 every_n_days 使用
+  day_of_month?: number | null; // 仅 monthly 使用，缺省为开始日期的日
+  start_date: string; // YYYY-MM-DD
+  end_date?: string | null;
+}
+
+// 1 单位 currency 在 rate_date 当天可兑换 rate 单位 quote_currency
+export interface ExchangeRate {
+  id: number;
+  currency: string;
+  quote_currency: string;
+  rate_date: string; // YYYY-MM-DD
+  rate: string;
+  created_at?: string;
+  updated_at?: string;
+}
+
+export interface ExchangeRateData {
+  currency: string;
+  quote_currency: string;
+  rate_date: string; // YYYY-MM-DD
+  rate: string;
+}
+
+export interface TransferData {
+  from_account_id: number;
+  to_account_id: number;
+  amount: string;
+  fee?: string | null;
+  note?: string | null;
+  occurred_at?: string; // ISO 8601 格式，缺省为当前时间
 }
 
 export interface RecordFilter {
-  record_type?: "expense" | "income";
+  record_type?: RecordType;
   category_id?: string;
+  account_id?: number; // 同时匹配转账的转出和转入账户
   start_date?: string; // ISO 8601 格式
   end_date?: string; // ISO 8601 格式;
+  tags_any?: string[]; // 带有其中任一标签
+  tags_all?: string[]; // 带有其中全部标签
+  limit?: number; // 每页条数，不传则返回全部
+  offset?: number;
+  cursor?: RecordCursor; // 优先于 offset
+}
+
+export interface RecordCursor {
+  occurred_at: string;
+  id: number;
+}
+
+export interface RecordPage {
+  records: ExpenseRecord[];
+  total: number;
+  has_more: boolean;
+  next_cursor: RecordCursor | null;
+}
+
+// 金额均已按记录日期的汇率换算为本位币
+export interface StatisticsReport {
+  currency: string; // 本位币
+  income_total: string;
+  expense_total: string;
+  net_balance: string;
+  record_count: number;
+  average_per_day: string; // 日均支出
+  max_record: ExpenseRecord | null;
+  missing_rates: string[]; // 缺少汇率、未计入统计的记录币种
+}
+
+export interface CategoryBreakdown {
+  category_id: string;
+  name: string;
+  icon: string;
+  color: string;
+  total: string;
+  count: number;
+  percentage: string; // 占同类型合计的百分比
+}
+
+export interface CategoryBreakdownReport {
+  categories: CategoryBreakdown[];
+  missing_rates: string[]; // 缺少汇率、未计入汇总的记录币种
+}
+
+export interface TagBreakdown {
+  tag_id: number;
+  name: string;
+  total: string;
+  count: number;
+  percentage: string; // 占范围内同类型全部记录合计的百分比，各标签之和可能超过 100
+}
+
+export interface TagBreakdownReport {
+  tags: TagBreakdown[];
+  missing_rates: string[]; // 缺少汇率、未计入汇总的记录币种
+}
+
+export type TimeGranularity = "day" | "week" | "month" | "year";
+
+export interface TimeBucket {
+  period: string; // 日/周为 YYYY-MM-DD，月为 YYYY-MM，年为 YYYY
+  start_date: string;
+  income_total: string;
+  expense_total: string;
+}
+
+export interface TimeSeries {
+  buckets: TimeBucket[];
+  missing_rates: string[]; // 缺少汇率、未计入汇总的记录币种
+}
+
+// 命令失败时 invoke 抛出的错误对象
+export type AppErrorCode =
+  | "NOT_FOUND"
+  | "VALIDATION"
+  | "CONFLICT"
+  | "DATABASE"
+  | "DATABASE_NOT_READY";
+
+export interface FieldError {
+  field: string;
+  message: string;
+}
+
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+  fields: FieldError[] | null; // 仅 VALIDATION 时有值
 }
 
 // Tauri Commands 类型定义
@@ -48,7 +364,15 @@ export interface DatabaseCommands {
   // Category commands
   get_categories: (args: {
     categoryType?: "expense" | "income";
+    includeArchived?: boolean;
+    sort?: CategorySort; // 默认 manual
+    locale?: string; // zh-CN、zh-TW、en，默认分类按此语言返回名称
   }) => Promise<Category[]>;
+  get_category_tree: (args: {
+    categoryType?: "expense" | "income";
+    includeArchived?: boolean;
+    locale?: string;
+  }) => Promise<CategoryNode[]>;
   get_category_by_id: (args: {
     categoryId: string;
   }) => Promise<Category | null>;
@@ -57,22 +381,131 @@ export interface DatabaseCommands {
     categoryId: string;
     categoryData: CategoryData;
   }) => Promise<Category>;
-  delete_category: (args: { categoryId: string }) => Promise<void>;
+  move_category: (args: {
+    categoryId: string;
+    parentId?: string | null; // 为空时移为顶级分类
+  }) => Promise<Category>;
+  reorder_categories: (args: { categoryIds: string[] }) => Promise<void>;
+  delete_category: (args: {
+    categoryId: string;
+    mode?: CategoryDeleteMode;
+  }) => Promise<void>;
+  merge_categories: (args: {
+    sourceCategoryId: string;
+    targetCategoryId: string;
+    archiveSource?: boolean; // 默认删除源分类
+  }) => Promise<number>;
+  set_category_archived: (args: {
+    categoryId: string;
+    archived: boolean;
+  }) => Promise<Category>;
+
+  // Account commands
+  get_accounts: () => Promise<Account[]>;
+  get_account_by_id: (args: { id: number }) => Promise<Account | null>;
+  create_account: (args: { accountData: AccountData }) => Promise<Account>;
+  update_account: (args: {
+    id: number;
+    accountData: AccountData;
+  }) => Promise<Account>;
+  delete_account: (args: { id: number }) => Promise<void>;
+  get_account_balances: () => Promise<AccountBalance[]>;
+
+  // Currency commands
+  get_base_currency: () => Promise<string>;
+  set_base_currency: (args: { currency: string }) => Promise<void>;
+  get_exchange_rates: (args: { currency?: string }) => Promise<ExchangeRate[]>;
+  set_exchange_rate: (args: {
+    rateData: ExchangeRateData;
+  }) => Promise<ExchangeRate>;
+  delete_exchange_rate: (args: { id: number }) => Promise<void>;
+  import_exchange_rates: (args: { content: string }) => Promise<number>;
+
+  // Recurring rule commands
+  get_recurring_rules: () => Promise<RecurringRule[]>;
+  create_recurring_rule: (args: {
+    ruleData: RecurringRuleData;
+  }) => Promise<RecurringRule>;
+  update_recurring_rule: (args: {
+    id: number;
+    ruleData: RecurringRuleData;
+  }) => Promise<RecurringRule>;
+  delete_recurring_rule: (args: { id: number }) => Promise<void>;
+
+  // Budget commands
+  get_budgets: () => Promise<Budget[]>;
+  create_budget: (args: { budgetData: BudgetData }) => Promise<Budget>;
+  update_budget: (args: {
+    id: number;
+    budgetData: BudgetData;
+  }) => Promise<Budget>;
+  delete_budget: (args: { id: number }) => Promise<void>;
+  get_budget_status: (args: {
+    date?: string; // YYYY-MM-DD 本地日期，缺省为今天
+    weekStart?: number; // 0 为周日，默认周一
+  }) => Promise<BudgetStatus[]>;
+  set_budget_allocations: (args: {
+    month?: string; // 所在月份中任意一天 YYYY-MM-DD，缺省为本月
+    allocations: EnvelopeAllocation[];
+  }) => Promise<BudgetAllocation[]>;
+  get_envelope_summary: (args: { month?: string }) => Promise<EnvelopeSummary>;
+
+  // Tag commands
+  get_tags: () => Promise<Tag[]>;
+  update_tag: (args: { id: number; tagData: TagData }) => Promise<Tag>;
+  delete_tag: (args: { id: number }) => Promise<void>;
 
   // Record commands
-  get_records: (args: { filter?: RecordFilter }) => Promise<ExpenseRecord[]>;
+  get_records: (args: { filter?: RecordFilter }) => Promise<RecordPage>;
+  search_records: (args: {
+    query: string; // 空格分隔的关键词，需全部匹配
+    filter?: RecordFilter; // 不支持 cursor，用 offset 分页
+  }) => Promise<RecordPage>;
   get_record_by_id: (args: { id: number }) => Promise<ExpenseRecord | null>;
   create_record: (args: {
     recordData: ExpenseRecordData;
+    locale?: string; // 预算提醒通知按此语言输出
+    weekStart?: number; // 周预算提醒按此划分周期，0 为周日，默认周一
   }) => Promise<ExpenseRecord>;
   update_record: (args: {
     id: number;
     recordData: ExpenseRecordData;
+    locale?: string; // 预算提醒通知按此语言输出
+    weekStart?: number; // 周预算提醒按此划分周期，0 为周日，默认周一
+  }) => Promise<ExpenseRecord>;
+  create_transfer: (args: {
+    transferData: TransferData;
+  }) => Promise<ExpenseRecord>;
+  update_transfer: (args: {
+    id: number;
+    transferData: TransferData;
   }) => Promise<ExpenseRecord>;
   delete_record: (args: { id: number }) => Promise<void>;
+  get_trash: () => Promise<ExpenseRecord[]>;
+  restore_record: (args: { id: number }) => Promise<ExpenseRecord>;
+  purge_record: (args: { id: number }) => Promise<void>;
+  empty_trash: () => Promise<number>;
   get_statistics: (args: {
     recordType?: "expense" | "income";
     startDate?: string;
     endDate?: string;
-  }) => Promise<string>;
+  }) => Promise<StatisticsReport>;
+  get_category_breakdown: (args: {
+    recordType: "expense" | "income";
+    startDate?: string;
+    endDate?: string;
+    rollUp?: boolean; // 子分类汇总到顶级分类
+    locale?: string; // 默认分类按此语言返回名称
+  }) => Promise<CategoryBreakdownReport>;
+  get_tag_breakdown: (args: {
+    recordType: "expense" | "income";
+    startDate?: string;
+    endDate?: string;
+  }) => Promise<TagBreakdownReport>;
+  get_time_series: (args: {
+    granularity: TimeGranularity;
+    startDate: string; // YYYY-MM-DD 本地日期
+    endDate: string;
+    weekStart?: number; // 0 为周日，默认 1（周一）
+  }) => Promise<TimeSeries>;
 }
@@ -1,18 +1,63 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
+  Account,
+  AccountBalance,
+  AccountData,
+  Budget,
+  BudgetAllocation,
+  BudgetData,
+  BudgetStatus,
   Category,
+  CategoryBreakdownReport,
   CategoryData,
+  CategoryDeleteMode,
+  CategoryNode,
+  CategorySort,
+  EnvelopeAllocation,
+  EnvelopeSummary,
+  ExchangeRate,
+  ExchangeRateData,
   ExpenseRecord,
   ExpenseRecordData,
   RecordFilter,
+  RecordPage,
+  RecurringRule,
+  RecurringRuleData,
+  StatisticsReport,
+  Tag,
+  TagBreakdownReport,
+  TagData,
+  TimeGranularity,
+  TimeSeries,
+  TransferData,
 } from "@/types/database";
 
 // 分类相关操作
 export class CategoryService {
   static async getCategories(
-    categoryType?: "expense" | "income"
+    categoryType?: "expense" | "income",
+    includeArchived?: boolean,
+    sort?: CategorySort,
+    locale: string = navigator.language
   ): Promise<Category[]> {
-    return await invoke("get_categories", { categoryType });
+    return await invoke("get_categories", {
+      categoryType,
+      includeArchived,
+      sort,
+      locale,
+    });
+  }
+
+  static async getCategoryTree(
+    categoryType?: "expense" | "income",
+    includeArchived?: boolean,
+    locale: string = navigator.language
+  ): Promise<CategoryNode[]> {
+    return await invoke("get_category_tree", {
+      categoryType,
+      includeArchived,
+      locale,
+    });
   }
 
   static async getCategoryById(categoryId: string): Promise<Category | null> {
@@ -30,54 +75,326 @@ export class CategoryService {
     return await invoke("update_category", { categoryId, categoryData });
   }
 
-  static async deleteCategory(categoryId: string): Promise<void> {
-    return await invoke("delete_category", { categoryId });
+  static async moveCategory(
+    categoryId: string,
+    parentId?: string | null
+  ): Promise<Category> {
+    return await invoke("move_category", { categoryId, parentId });
+  }
+
+  // 传入完整的分类顺序，未列出的分类排在后面
+  static async reorderCategories(categoryIds: string[]): Promise<void> {
+    return await invoke("reorder_categories", { categoryIds });
+  }
+
+  static async deleteCategory(
+    categoryId: string,
+    mode?: CategoryDeleteMode
+  ): Promise<void> {
+    return await invoke("delete_category", { categoryId, mode });
+  }
+
+  // 把源分类的记录全部转到目标分类，返回转移的条数
+  static async mergeCategories(
+    sourceCategoryId: string,
+    targetCategoryId: string,
+    archiveSource?: boolean
+  ): Promise<number> {
+    return await invoke("merge_categories", {
+      sourceCategoryId,
+      targetCategoryId,
+      archiveSource,
+    });
+  }
+
+  static async setCategoryArchived(
+    categoryId: string,
+    archived: boolean
+  ): Promise<Category> {
+    return await invoke("set_category_archived", { categoryId, archived });
+  }
+}
+
+// 账户相关操作
+export class AccountService {
+  static async getAccounts(): Promise<Account[]> {
+    return await invoke("get_accounts");
+  }
+
+  static async getAccountById(id: number): Promise<Account | null> {
+    return await invoke("get_account_by_id", { id });
+  }
+
+  static async createAccount(accountData: AccountData): Promise<Account> {
+    return await invoke("create_account", { accountData });
+  }
+
+  static async updateAccount(
+    id: number,
+    accountData: AccountData
+  ): Promise<Account> {
+    return await invoke("update_account", { id, accountData });
+  }
+
+  // 仍有记录的账户无法删除
+  static async deleteAccount(id: number): Promise<void> {
+    return await invoke("delete_account", { id });
+  }
+
+  static async getAccountBalances(): Promise<AccountBalance[]> {
+    return await invoke("get_account_balances");
+  }
+}
+
+// 本位币与汇率相关操作
+export class CurrencyService {
+  static async getBaseCurrency(): Promise<string> {
+    return await invoke("get_base_currency");
+  }
+
+  static async setBaseCurrency(currency: string): Promise<void> {
+    return await invoke("set_base_currency", { currency });
+  }
+
+  static async getExchangeRates(currency?: string): Promise<ExchangeRate[]> {
+    return await invoke("get_exchange_rates", { currency });
+  }
+
+  // 同一天同一币种对已有汇率时覆盖
+  static async setExchangeRate(
+    rateData: ExchangeRateData
+  ): Promise<ExchangeRate> {
+    return await invoke("set_exchange_rate", { rateData });
+  }
+
+  static async deleteExchangeRate(id: number): Promise<void> {
+    return await invoke("delete_exchange_rate", { id });
+  }
+
+  // content 为 CSV 文本，每行 日期,币种,报价币种,汇率，返回导入条数
+  static async importExchangeRates(content: string): Promise<number> {
+    return await invoke("import_exchange_rates", { content });
+  }
+}
+
+// 周期记录相关操作，到期记录由后台生成，生成后会发出 recurring-records-created 事件
+export class RecurringService {
+  static async getRecurringRules(): Promise<RecurringRule[]> {
+    return await invoke("get_recurring_rules");
+  }
+
+  // 开始日期早于今天时会立即补上之前的记录
+  static async createRecurringRule(
+    ruleData: RecurringRuleData
+  ): Promise<RecurringRule> {
+    return await invoke("create_recurring_rule", { ruleData });
+  }
+
+  // 只影响之后生成的记录
+  static async updateRecurringRule(
+    id: number,
+    ruleData: RecurringRuleData
+  ): Promise<RecurringRule> {
+    return await invoke("update_recurring_rule", { id, ruleData });
+  }
+
+  // 已生成的记录会保留
+  static async deleteRecurringRule(id: number): Promise<void> {
+    return await invoke("delete_recurring_rule", { id });
+  }
+}
+
+// 预算相关操作，支出记录使预算首次达到提醒阈值时会发出 budget-alert 事件
+export class BudgetService {
+  static async getBudgets(): Promise<Budget[]> {
+    return await invoke("get_budgets");
+  }
+
+  // 同一周期同一分类只能有一个预算
+  static async createBudget(budgetData: BudgetData): Promise<Budget> {
+    return await invoke("create_budget", { budgetData });
+  }
+
+  static async updateBudget(
+    id: number,
+    budgetData: BudgetData
+  ): Promise<Budget> {
+    return await invoke("update_budget", { id, budgetData });
+  }
+
+  static async deleteBudget(id: number): Promise<void> {
+    return await invoke("delete_budget", { id });
+  }
+
+  static async getBudgetStatus(
+    date?: string,
+    weekStart?: number
+  ): Promise<BudgetStatus[]> {
+    return await invoke("get_budget_status", { date, weekStart });
+  }
+
+  // 只能分配本月及之后的月份，重复分配会覆盖
+  static async setBudgetAllocations(
+    allocations: EnvelopeAllocation[],
+    month?: string
+  ): Promise<BudgetAllocation[]> {
+    return await invoke("set_budget_allocations", { month, allocations });
+  }
+
+  static async getEnvelopeSummary(month?: string): Promise<EnvelopeSummary> {
+    return await invoke("get_envelope_summary", { month });
+  }
+}
+
+// 标签相关操作，标签随记录创建
+export class TagService {
+  static async getTags(): Promise<Tag[]> {
+    return await invoke("get_tags");
+  }
+
+  // 不能重命名为已有的标签
+  static async updateTag(id: number, tagData: TagData): Promise<Tag> {
+    return await invoke("update_tag", { id, tagData });
+  }
+
+  // 只从记录上去掉该标签，记录本身保留
+  static async deleteTag(id: number): Promise<void> {
+    return await invoke("delete_tag", { id });
   }
 }
 
 // 记录相关操作
 export class RecordService {
-  static async getRecords(filter?: RecordFilter): Promise<ExpenseRecord[]> {
+  static async getRecords(filter?: RecordFilter): Promise<RecordPage> {
     return await invoke("get_records", { filter });
   }
 
+  // 在备注、分类名称和标签中搜索，按相关度排列；不支持 cursor，用 offset 分页
+  static async searchRecords(
+    query: string,
+    filter?: RecordFilter
+  ): Promise<RecordPage> {
+    return await invoke("search_records", { query, filter });
+  }
+
   static async getRecordById(id: number): Promise<ExpenseRecord | null> {
     return await invoke("get_record_by_id", { id });
   }
 
   static async createRecord(
-    recordData: ExpenseRecordData
+    recordData: ExpenseRecordData,
+    locale: string = navigator.language,
+    weekStart?: number
   ): Promise<ExpenseRecord> {
-    return await invoke("create_record", { recordData });
+    return await invoke("create_record", { recordData, locale, weekStart });
   }
 
   static async updateRecord(
     id: number,
-    recordData: ExpenseRecordData
+    recordData: ExpenseRecordData,
+    locale: string = navigator.language,
+    weekStart?: number
+  ): Promise<ExpenseRecord> {
+    return await invoke("update_record", { id, recordData, locale, weekStart });
+  }
+
+  static async createTransfer(
+    transferData: TransferData
+  ): Promise<ExpenseRecord> {
+    return await invoke("create_transfer", { transferData });
+  }
+
+  static async updateTransfer(
+    id: number,
+    transferData: TransferData
   ): Promise<ExpenseRecord> {
-    return await invoke("update_record", { id, recordData });
+    return await invoke("update_transfer", { id, transferData });
   }
 
+  // 移入回收站，转账记录同样适用
   static async deleteRecord(id: number): Promise<void> {
     return await invoke("delete_record", { id });
   }
 
+  static async getTrash(): Promise<ExpenseRecord[]> {
+    return await invoke("get_trash");
+  }
+
+  static async restoreRecord(id: number): Promise<ExpenseRecord> {
+    return await invoke("restore_record", { id });
+  }
+
+  // 彻底删除
+  static async purgeRecord(id: number): Promise<void> {
+    return await invoke("purge_record", { id });
+  }
+
+  static async emptyTrash(): Promise<number> {
+    return await invoke("empty_trash");
+  }
+
   static async getStatistics(
     recordType?: "expense" | "income",
     startDate?: string,
     endDate?: string
-  ): Promise<number> {
-    const result = await invoke("get_statistics", {
+  ): Promise<StatisticsReport> {
+    return await invoke("get_statistics", {
       recordType,
       startDate,
       endDate,
     });
-    return parseFloat(result as string);
+  }
+
+  static async getCategoryBreakdown(
+    recordType: "expense" | "income",
+    startDate?: string,
+    endDate?: string,
+    rollUp?: boolean,
+    locale: string = navigator.language
+  ): Promise<CategoryBreakdownReport> {
+    return await invoke("get_category_breakdown", {
+      recordType,
+      startDate,
+      endDate,
+      rollUp,
+      locale,
+    });
+  }
+
+  static async getTagBreakdown(
+    recordType: "expense" | "income",
+    startDate?: string,
+    endDate?: string
+  ): Promise<TagBreakdownReport> {
+    return await invoke("get_tag_breakdown", {
+      recordType,
+      startDate,
+      endDate,
+    });
+  }
+
+  static async getTimeSeries(
+    granularity: TimeGranularity,
+    startDate: string,
+    endDate: string,
+    weekStart?: number
+  ): Promise<TimeSeries> {
+    return await invoke("get_time_series", {
+      granularity,
+      startDate,
+      endDate,
+      weekStart,
+    });
   }
 }
 
 // 便捷方法
 export const db = {
   categories: CategoryService,
+  accounts: AccountService,
+  currency: CurrencyService,
+  recurring: RecurringService,
+  budgets: BudgetService,
+  tags: TagService,
   records: RecordService,
 };